@@ -0,0 +1,529 @@
+// Computer players. Both searches only ever look at the engine's legal moves
+// so they play by the same rules as everyone else.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::board::Board;
+use crate::game_state::{GameResult, GameState, Move};
+use crate::hex::Hex;
+use crate::piece::{Bug, Piece, PlayerNumber};
+use crate::rules::{find_bug_locations, find_pinned_positions};
+use crate::zobrist::{Bound, Entry, TranspositionTable};
+
+const WIN_SCORE: i32 = 1_000_000;
+// Anything past this is a win some number of plies away
+const WIN_THRESHOLD: i32 = WIN_SCORE - 10_000;
+const QUEEN_NEIGHBOR_WEIGHT: i32 = 100;
+const MOBILITY_WEIGHT: i32 = 2;
+const PINNED_WEIGHT: i32 = 10;
+// Slots in the alpha-beta transposition table
+const TRANSPOSITION_TABLE_SIZE: usize = 1 << 18;
+// Playouts that go on this long are scored by the evaluation instead
+const PLAYOUT_MOVE_CAP: usize = 40;
+// How much UCT favors trying less visited moves
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+// The depth is how many plies alpha-beta looks ahead, or how deep the MCTS
+// tree has to grow. Iterations are the positions alpha-beta searches, or the
+// playouts MCTS plays.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Budget {
+    Depth(usize),
+    Iterations(usize),
+    Time(Duration),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Strategy {
+    AlphaBeta,
+    Mcts,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Ai {
+    pub strategy: Strategy,
+    pub budget: Budget,
+}
+
+impl Ai {
+    pub fn new(strategy: Strategy, budget: Budget) -> Self {
+        Ai { strategy, budget }
+    }
+
+    // The move the player with the turn should make, or a pass when the game
+    // is already over
+    pub fn best_move(&self, game_state: &GameState) -> Move {
+        let legal_move_vec = game_state.legal_moves();
+        match legal_move_vec.as_slice() {
+            [] => return Move::Pass,
+            [only_move] => return *only_move,
+            _ => {}
+        }
+        match self.strategy {
+            Strategy::AlphaBeta => AlphaBeta::new(self.budget).search_root(game_state),
+            Strategy::Mcts => Mcts::new(self.budget).search_root(game_state),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////
+// Evaluation
+//////////////////////////////////////////////////////////////////////
+
+fn other_player(player: PlayerNumber) -> PlayerNumber {
+    match player {
+        PlayerNumber::One => PlayerNumber::Two,
+        PlayerNumber::Two => PlayerNumber::One,
+        PlayerNumber::None => PlayerNumber::None,
+    }
+}
+
+// Score of a finished game for the player with the turn. Quicker wins and
+// slower losses are worth more.
+fn score_result(game_result: GameResult, player: PlayerNumber, ply: usize) -> i32 {
+    let winner = match game_result {
+        GameResult::PlayerOneWins => PlayerNumber::One,
+        GameResult::PlayerTwoWins => PlayerNumber::Two,
+        GameResult::Draw => return 0,
+    };
+    if winner == player {
+        WIN_SCORE - ply as i32
+    } else {
+        ply as i32 - WIN_SCORE
+    }
+}
+
+fn count_queen_neighbors(board: &Board, player: PlayerNumber) -> i32 {
+    let Ok(position) = board.find_piece(Piece::new(Bug::Queen, player, 1)) else {
+        return 0;
+    };
+    board
+        .neighboring_stacks(position)
+        .iter()
+        .filter(|stack| !stack.is_empty())
+        .count() as i32
+}
+
+// Destinations the player's free pieces could reach if it were their turn
+fn count_mobility(board: &Board, player: PlayerNumber, pinned_position_set: &HashSet<Hex>) -> i32 {
+    if board.find_piece(Piece::new(Bug::Queen, player, 1)).is_err() {
+        return 0;
+    }
+    let mut mobility = 0;
+    for position in board.occupied_positions() {
+        let piece = board.top(position);
+        if piece.player != player || pinned_position_set.contains(&position) {
+            continue;
+        }
+        mobility += find_bug_locations(board, position, piece.bug).len();
+    }
+    mobility as i32
+}
+
+fn count_pinned_pieces(
+    board: &Board,
+    player: PlayerNumber,
+    pinned_position_set: &HashSet<Hex>,
+) -> i32 {
+    pinned_position_set
+        .iter()
+        .filter(|position| board.top(**position).player == player)
+        .count() as i32
+}
+
+// How good the position is for the player with the turn. Pieces around the
+// enemy queen matter most, then pieces that are free to move.
+pub fn evaluate(game_state: &GameState) -> i32 {
+    let board = game_state.board();
+    let player = game_state.player_with_turn().number;
+    let opponent = other_player(player);
+    let pinned_position_set = find_pinned_positions(board);
+
+    let queen_score = count_queen_neighbors(board, opponent) - count_queen_neighbors(board, player);
+    let mobility_score = count_mobility(board, player, &pinned_position_set)
+        - count_mobility(board, opponent, &pinned_position_set);
+    let pinned_score = count_pinned_pieces(board, opponent, &pinned_position_set)
+        - count_pinned_pieces(board, player, &pinned_position_set);
+
+    QUEEN_NEIGHBOR_WEIGHT * queen_score
+        + MOBILITY_WEIGHT * mobility_score
+        + PINNED_WEIGHT * pinned_score
+}
+
+//////////////////////////////////////////////////////////////////////
+// Alpha-beta
+//////////////////////////////////////////////////////////////////////
+
+struct AlphaBeta {
+    max_depth: usize,
+    deadline: Option<Instant>,
+    max_node_count: Option<usize>,
+    node_count: usize,
+    transposition_table: TranspositionTable,
+    // Best move of the last depth that was searched to the end
+    root_best_move: Option<Move>,
+}
+
+impl AlphaBeta {
+    fn new(budget: Budget) -> Self {
+        let (max_depth, deadline, max_node_count) = match budget {
+            Budget::Depth(max_depth) => (max_depth.max(1), None, None),
+            Budget::Iterations(max_node_count) => (usize::MAX, None, Some(max_node_count.max(1))),
+            Budget::Time(duration) => (usize::MAX, Some(Instant::now() + duration), None),
+        };
+        AlphaBeta {
+            max_depth,
+            deadline,
+            max_node_count,
+            node_count: 0,
+            transposition_table: TranspositionTable::new(TRANSPOSITION_TABLE_SIZE),
+            root_best_move: None,
+        }
+    }
+
+    fn out_of_budget(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+            || self
+                .max_node_count
+                .is_some_and(|max_node_count| self.node_count >= max_node_count)
+    }
+
+    // Iterative deepening. A depth that runs out of budget is thrown away and
+    // the best move of the last finished depth is played. Each depth fills
+    // the transposition table with the best moves to try first in the next.
+    fn search_root(&mut self, game_state: &GameState) -> Move {
+        let mut game_state = game_state.clone();
+        let first_move = game_state.legal_moves()[0];
+
+        for depth in 1..=self.max_depth {
+            let Some(score) = self.search(&mut game_state, depth, -WIN_SCORE - 1, WIN_SCORE + 1, 0)
+            else {
+                break;
+            };
+
+            // No point looking deeper once a win or loss is certain
+            if score.abs() >= WIN_SCORE - depth as i32 {
+                break;
+            }
+        }
+        self.root_best_move.unwrap_or(first_move)
+    }
+
+    // Negamax, so every score is from the view of the player with the turn.
+    // Moves are played and taken back on the one game state.
+    fn search(
+        &mut self,
+        game_state: &mut GameState,
+        depth: usize,
+        mut alpha: i32,
+        beta: i32,
+        ply: usize,
+    ) -> Option<i32> {
+        self.node_count += 1;
+        if let Some(game_result) = game_state.result() {
+            let player = game_state.player_with_turn().number;
+            return Some(score_result(game_result, player, ply));
+        }
+        if depth == 0 {
+            return Some(evaluate(game_state));
+        }
+        if self.out_of_budget() {
+            return None;
+        }
+
+        let hash = game_state.hash();
+        let original_alpha = alpha;
+        let mut hash_move = None;
+        if let Some(entry) = self.transposition_table.get(hash) {
+            hash_move = entry.best_move;
+            let score = score_from_table(entry.score, ply);
+            if entry.depth >= depth && ply > 0 {
+                match entry.bound {
+                    Bound::Exact => return Some(score),
+                    Bound::Lower if score >= beta => return Some(score),
+                    Bound::Upper if score <= alpha => return Some(score),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut legal_move_vec = game_state.legal_moves();
+        if let Some(index) = legal_move_vec
+            .iter()
+            .position(|legal_move| Some(*legal_move) == hash_move)
+        {
+            legal_move_vec[..=index].rotate_right(1);
+        }
+
+        let mut best_score = -WIN_SCORE - 1;
+        let mut best_move = None;
+        for legal_move in legal_move_vec {
+            game_state.play(legal_move);
+            let score = self.search(game_state, depth - 1, -beta, -alpha, ply + 1);
+            game_state.undo();
+            let score = -score?;
+            if score > best_score {
+                best_score = score;
+                best_move = Some(legal_move);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best_score <= original_alpha {
+            Bound::Upper
+        } else if best_score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.transposition_table.insert(Entry {
+            hash,
+            depth,
+            score: score_to_table(best_score, ply),
+            bound,
+            best_move,
+        });
+        if ply == 0 {
+            self.root_best_move = best_move;
+        }
+        Some(best_score)
+    }
+}
+
+// Win scores count plies from the root, but the table is shared between
+// every ply, so they are stored counting from the position itself
+fn score_to_table(score: i32, ply: usize) -> i32 {
+    if score >= WIN_THRESHOLD {
+        score + ply as i32
+    } else if score <= -WIN_THRESHOLD {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+fn score_from_table(score: i32, ply: usize) -> i32 {
+    if score >= WIN_THRESHOLD {
+        score - ply as i32
+    } else if score <= -WIN_THRESHOLD {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+//////////////////////////////////////////////////////////////////////
+// Monte Carlo tree search
+//////////////////////////////////////////////////////////////////////
+
+// Xorshift is plenty for picking playout moves
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default();
+        Rng { state: nanos | 1 }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+struct Node {
+    // The move that led here and the player who made it
+    last_move: Move,
+    player: PlayerNumber,
+    parent: Option<usize>,
+    child_vec: Vec<usize>,
+    untried_move_vec: Vec<Move>,
+    visits: f64,
+    // Summed rewards for the player who made the move
+    reward: f64,
+}
+
+struct Mcts {
+    budget: Budget,
+    started: Instant,
+    rng: Rng,
+}
+
+impl Mcts {
+    fn new(budget: Budget) -> Self {
+        Mcts {
+            budget,
+            started: Instant::now(),
+            rng: Rng::from_time(),
+        }
+    }
+
+    fn has_budget(&self, iterations: usize, tree_depth: usize) -> bool {
+        match self.budget {
+            Budget::Depth(max_depth) => tree_depth < max_depth.max(1),
+            Budget::Iterations(max_iterations) => iterations < max_iterations.max(1),
+            Budget::Time(duration) => iterations == 0 || self.started.elapsed() < duration,
+        }
+    }
+
+    // The most visited move at the root is the one played
+    fn search_root(&mut self, game_state: &GameState) -> Move {
+        let mut node_vec = vec![Node {
+            last_move: Move::Pass,
+            player: other_player(game_state.player_with_turn().number),
+            parent: None,
+            child_vec: vec![],
+            untried_move_vec: game_state.legal_moves(),
+            visits: 0.0,
+            reward: 0.0,
+        }];
+
+        let mut iterations = 0;
+        let mut tree_depth = 0;
+        while self.has_budget(iterations, tree_depth) {
+            match self.iterate(game_state, &mut node_vec) {
+                Some(depth) => tree_depth = tree_depth.max(depth),
+                // The most promising line already ends the game, so growing
+                // the tree deeper won't change the move
+                None if matches!(self.budget, Budget::Depth(_)) => break,
+                None => {}
+            }
+            iterations += 1;
+        }
+
+        let best_child = node_vec[0]
+            .child_vec
+            .iter()
+            .max_by(|a, b| node_vec[**a].visits.total_cmp(&node_vec[**b].visits))
+            .expect("The root has at least one move");
+        node_vec[*best_child].last_move
+    }
+
+    // Selection, expansion, playout and backpropagation. Gives the depth of
+    // the node the playout started from, or none when its game is over.
+    fn iterate(&mut self, game_state: &GameState, node_vec: &mut Vec<Node>) -> Option<usize> {
+        let mut game_state = game_state.clone();
+        let mut index = 0;
+        let mut depth = 0;
+        while node_vec[index].untried_move_vec.is_empty() && !node_vec[index].child_vec.is_empty() {
+            index = select_child(node_vec, index);
+            game_state.play(node_vec[index].last_move);
+            depth += 1;
+        }
+
+        if !node_vec[index].untried_move_vec.is_empty() {
+            let untried_index = self.rng.below(node_vec[index].untried_move_vec.len());
+            let expanded_move = node_vec[index].untried_move_vec.swap_remove(untried_index);
+            let player = game_state.player_with_turn().number;
+            game_state.play(expanded_move);
+            node_vec.push(Node {
+                last_move: expanded_move,
+                player,
+                parent: Some(index),
+                child_vec: vec![],
+                untried_move_vec: game_state.legal_moves(),
+                visits: 0.0,
+                reward: 0.0,
+            });
+            let child = node_vec.len() - 1;
+            node_vec[index].child_vec.push(child);
+            index = child;
+            depth += 1;
+        }
+        let game_over = game_state.result().is_some();
+
+        let winner = self.playout(&mut game_state);
+        let mut current = Some(index);
+        while let Some(current_index) = current {
+            let node = &mut node_vec[current_index];
+            node.visits += 1.0;
+            node.reward += match winner {
+                Some(winner) if winner == node.player => 1.0,
+                Some(PlayerNumber::None) | None => 0.5,
+                Some(_) => 0.0,
+            };
+            current = node.parent;
+        }
+        (!game_over).then_some(depth)
+    }
+
+    // Plays random moves until the game ends, leaning towards moves onto the
+    // enemy queen. A playout that hits the cap is given to whoever the
+    // evaluation favors.
+    fn playout(&mut self, game_state: &mut GameState) -> Option<PlayerNumber> {
+        for _ in 0..PLAYOUT_MOVE_CAP {
+            if let Some(game_result) = game_state.result() {
+                return Some(match game_result {
+                    GameResult::PlayerOneWins => PlayerNumber::One,
+                    GameResult::PlayerTwoWins => PlayerNumber::Two,
+                    GameResult::Draw => PlayerNumber::None,
+                });
+            }
+            let legal_move_vec = game_state.legal_moves();
+            let attacking_move_vec = find_attacking_moves(game_state, &legal_move_vec);
+            let random_move = if !attacking_move_vec.is_empty() && self.rng.below(2) == 0 {
+                attacking_move_vec[self.rng.below(attacking_move_vec.len())]
+            } else {
+                legal_move_vec[self.rng.below(legal_move_vec.len())]
+            };
+            game_state.play(random_move);
+        }
+
+        let player = game_state.player_with_turn().number;
+        match evaluate(game_state) {
+            score if score > 0 => Some(player),
+            score if score < 0 => Some(other_player(player)),
+            _ => None,
+        }
+    }
+}
+
+// Moves that end next to the enemy queen
+fn find_attacking_moves(game_state: &GameState, legal_move_vec: &[Move]) -> Vec<Move> {
+    let opponent = game_state.player_without_turn().number;
+    let queen = Piece::new(Bug::Queen, opponent, 1);
+    let Ok(queen_position) = game_state.board().find_piece(queen) else {
+        return vec![];
+    };
+    legal_move_vec
+        .iter()
+        .filter(|legal_move| match legal_move {
+            Move::Move {
+                source,
+                destination,
+            } => *source != queen_position && destination.distance(queen_position) == 1,
+            _ => false,
+        })
+        .copied()
+        .collect()
+}
+
+// Upper confidence bound for trees
+fn select_child(node_vec: &[Node], index: usize) -> usize {
+    let parent_visits = node_vec[index].visits.max(1.0);
+    let uct = |child: usize| {
+        let node = &node_vec[child];
+        node.reward / node.visits + EXPLORATION * (parent_visits.ln() / node.visits).sqrt()
+    };
+    *node_vec[index]
+        .child_vec
+        .iter()
+        .max_by(|a, b| uct(**a).total_cmp(&uct(**b)))
+        .unwrap()
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use crate::hex::{Direction, Hex};
+use crate::piece::{Bug, Piece, PlayerNumber};
+use crate::zobrist::piece_key;
+
+// Every beetle and mosquito stacked on top of one other piece
+pub const MAX_STACK_HEIGHT: usize = 7;
+static EMPTY_STACK: Stack = Stack::new();
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FindError {
+    NotFound,
+}
+
+////////////////////////////////////////////////////////////////////////
+
+// Pieces sitting on a single board position, bottom first
+#[derive(Debug, Copy, Clone)]
+pub struct Stack {
+    pieces: [Piece; MAX_STACK_HEIGHT],
+    height: usize,
+}
+
+impl Stack {
+    const fn new() -> Self {
+        Stack {
+            pieces: [Piece::new(Bug::None, PlayerNumber::None, 0); MAX_STACK_HEIGHT],
+            height: 0,
+        }
+    }
+
+    pub fn top(&self) -> Piece {
+        if self.height == 0 {
+            return Piece::new(Bug::None, PlayerNumber::None, 0);
+        }
+        self.pieces[self.height - 1]
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.height == 0
+    }
+
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces[..self.height]
+    }
+
+    fn push(&mut self, piece: Piece) {
+        assert!(self.height < MAX_STACK_HEIGHT);
+        self.pieces[self.height] = piece;
+        self.height += 1;
+    }
+
+    fn pop(&mut self) -> Piece {
+        assert!(self.height > 0);
+        self.height -= 1;
+        let piece = self.pieces[self.height];
+        self.pieces[self.height] = Piece::new(Bug::None, PlayerNumber::None, 0);
+        piece
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+// Only occupied hexes are stored so the hive can grow in any direction
+#[derive(Debug, Clone, Default)]
+pub struct Board {
+    stacks: HashMap<Hex, Stack>,
+    // Zobrist hash of every piece, kept up to date as they come and go
+    hash: u64,
+}
+
+impl Board {
+    pub fn new() -> Self {
+        Board {
+            stacks: HashMap::new(),
+            hash: 0,
+        }
+    }
+
+    pub fn stack(&self, position: Hex) -> &Stack {
+        self.stacks.get(&position).unwrap_or(&EMPTY_STACK)
+    }
+
+    pub fn top(&self, position: Hex) -> Piece {
+        self.stack(position).top()
+    }
+
+    pub fn height(&self, position: Hex) -> usize {
+        self.stack(position).height()
+    }
+
+    pub fn is_occupied(&self, position: Hex) -> bool {
+        self.stacks.contains_key(&position)
+    }
+
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub(crate) fn push(&mut self, position: Hex, piece: Piece) {
+        let stack = self.stacks.entry(position).or_insert_with(Stack::new);
+        stack.push(piece);
+        self.hash ^= piece_key(piece, position, stack.height());
+    }
+
+    pub(crate) fn pop(&mut self, position: Hex) -> Piece {
+        let stack = self.stacks.get_mut(&position).expect("Nothing to pop");
+        self.hash ^= piece_key(stack.top(), position, stack.height());
+        let piece = stack.pop();
+        if stack.is_empty() {
+            self.stacks.remove(&position);
+        }
+        piece
+    }
+
+    pub fn height_in_direction(&self, position: Hex, direction: Direction) -> usize {
+        self.height(position.neighbor(direction))
+    }
+
+    pub fn neighboring_stacks(&self, position: Hex) -> Vec<Stack> {
+        position
+            .neighbors()
+            .iter()
+            .map(|neighbor| *self.stack(*neighbor))
+            .collect()
+    }
+
+    // Sorted so that anything built from it comes out in the same order
+    pub fn occupied_positions(&self) -> Vec<Hex> {
+        let mut position_vec: Vec<Hex> = self.stacks.keys().copied().collect();
+        position_vec.sort();
+        position_vec
+    }
+
+    pub fn find_piece(&self, piece: Piece) -> Result<Hex, FindError> {
+        for (position, stack) in &self.stacks {
+            // The piece may be buried under a beetle
+            if stack.pieces().contains(&piece) {
+                return Ok(*position);
+            }
+        }
+        Err(FindError::NotFound)
+    }
+}
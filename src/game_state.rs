@@ -0,0 +1,430 @@
+use crate::board::Board;
+use crate::hex::Hex;
+use crate::piece::{create_hand, Bug, Expansions, Piece, PlayerNumber};
+use crate::rules::{
+    check_for_surrounded_queen, find_bug_locations, find_pinned_positions, find_throwable_pieces,
+    find_throwing_locations,
+};
+use crate::zobrist::side_key;
+
+// The queen has to be on the board by the end of this turn
+pub const QUEEN_DEADLINE_TURN: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub number: PlayerNumber,
+    pub hand: Vec<Piece>,
+    pub turns_taken: usize,
+}
+
+impl Player {
+    fn new(number: PlayerNumber, expansions: Expansions) -> Self {
+        Player {
+            number,
+            hand: create_hand(number, expansions),
+            turns_taken: 0,
+        }
+    }
+
+    pub fn has_queen_in_hand(&self) -> bool {
+        self.hand.iter().any(|piece| piece.bug == Bug::Queen)
+    }
+
+    pub fn must_place_queen(&self) -> bool {
+        self.has_queen_in_hand() && self.turns_taken + 1 >= QUEEN_DEADLINE_TURN
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Move {
+    Place {
+        bug: Bug,
+        destination: Hex,
+    },
+    Move {
+        source: Hex,
+        destination: Hex,
+    },
+    // A pillbug lifting the piece at the source over itself
+    Throw {
+        pillbug: Hex,
+        source: Hex,
+        destination: Hex,
+    },
+    Pass,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GameResult {
+    PlayerOneWins,
+    PlayerTwoWins,
+    Draw,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    IllegalMove,
+    GameOver,
+}
+
+////////////////////////////////////////////////////////////////////////
+
+// What a move changed that can't be worked out from the move itself
+#[derive(Debug, Copy, Clone)]
+struct HistoryEntry {
+    played_move: Move,
+    // Where the placed piece sat in the hand
+    hand_index: usize,
+    last_moved_position: Option<Hex>,
+    frozen_position: Option<Hex>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GameState {
+    board: Board,
+    player_with_turn: Player,
+    player_without_turn: Player,
+    last_moved_position: Option<Hex>,
+    // A piece thrown by a pillbug can't move on the following turn
+    frozen_position: Option<Hex>,
+    expansions: Expansions,
+    result: Option<GameResult>,
+    history: Vec<HistoryEntry>,
+    // Hash of every position so far, the current one last
+    hash_history: Vec<u64>,
+    repetition_draw: bool,
+}
+
+impl GameState {
+    pub fn new(expansions: Expansions) -> Self {
+        GameState {
+            board: Board::new(),
+            player_with_turn: Player::new(PlayerNumber::One, expansions),
+            player_without_turn: Player::new(PlayerNumber::Two, expansions),
+            last_moved_position: None,
+            frozen_position: None,
+            expansions,
+            result: None,
+            history: vec![],
+            hash_history: vec![side_key(PlayerNumber::One)],
+            repetition_draw: true,
+        }
+    }
+
+    // Sets up a position without playing up to it. Pieces are given bottom
+    // first and come out of their player's hand, with every placed piece
+    // counting as a turn taken. Nothing else checks that the position could
+    // come up in a game. Gives nothing when a piece isn't in a hand.
+    pub fn from_position(
+        expansions: Expansions,
+        placed_piece_vec: &[(Hex, Piece)],
+        player_with_turn: PlayerNumber,
+    ) -> Option<Self> {
+        let mut game_state = GameState::new(expansions);
+        for (position, piece) in placed_piece_vec {
+            let player = if piece.player == game_state.player_with_turn.number {
+                &mut game_state.player_with_turn
+            } else {
+                &mut game_state.player_without_turn
+            };
+            let hand_index = player.hand.iter().position(|held| held == piece)?;
+            player.hand.remove(hand_index);
+            player.turns_taken += 1;
+            game_state.board.push(*position, *piece);
+        }
+        if player_with_turn != game_state.player_with_turn.number {
+            std::mem::swap(
+                &mut game_state.player_with_turn,
+                &mut game_state.player_without_turn,
+            );
+        }
+        game_state.hash_history = vec![game_state.hash()];
+        game_state.result = game_state.check_for_game_result();
+        Some(game_state)
+    }
+
+    // The third time a position comes up with the same player to move the
+    // game is drawn, unless this is turned off
+    pub fn set_repetition_draw(&mut self, enabled: bool) {
+        self.repetition_draw = enabled;
+    }
+
+    pub fn repetition_draw(&self) -> bool {
+        self.repetition_draw
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn player_with_turn(&self) -> &Player {
+        &self.player_with_turn
+    }
+
+    pub fn player_without_turn(&self) -> &Player {
+        &self.player_without_turn
+    }
+
+    pub fn expansions(&self) -> Expansions {
+        self.expansions
+    }
+
+    pub fn result(&self) -> Option<GameResult> {
+        self.result
+    }
+
+    // Zobrist hash of the pieces and the player with the turn
+    pub fn hash(&self) -> u64 {
+        self.board.hash() ^ side_key(self.player_with_turn.number)
+    }
+
+    pub fn move_count(&self) -> usize {
+        self.history.len()
+    }
+
+    // Every move the player with the turn can make. A player with nothing
+    // else to do has to pass, and nobody moves once the game is over.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut move_vec = vec![];
+        if self.result.is_some() {
+            return move_vec;
+        }
+
+        let placeable_position_vec = self.find_placeable_positions();
+        let mut placed_bug_vec = vec![];
+        for piece in &self.player_with_turn.hand {
+            if placed_bug_vec.contains(&piece.bug) {
+                continue;
+            }
+            if self.player_with_turn.must_place_queen() && piece.bug != Bug::Queen {
+                continue;
+            }
+            placed_bug_vec.push(piece.bug);
+            for destination in &placeable_position_vec {
+                move_vec.push(Move::Place {
+                    bug: piece.bug,
+                    destination: *destination,
+                });
+            }
+        }
+
+        let pinned_position_set = find_pinned_positions(&self.board);
+        for source in self.find_movable_positions() {
+            let piece = self.board.top(source);
+            if !pinned_position_set.contains(&source) {
+                for destination in find_bug_locations(&self.board, source, piece.bug) {
+                    move_vec.push(Move::Move {
+                        source,
+                        destination,
+                    });
+                }
+            }
+
+            // A pillbug that can't move may still lift its neighbors
+            for thrown_position in find_throwable_pieces(
+                &self.board,
+                source,
+                self.last_moved_position,
+                &pinned_position_set,
+            ) {
+                for destination in find_throwing_locations(&self.board, source, thrown_position) {
+                    move_vec.push(Move::Throw {
+                        pillbug: source,
+                        source: thrown_position,
+                        destination,
+                    });
+                }
+            }
+        }
+
+        if move_vec.is_empty() {
+            move_vec.push(Move::Pass);
+        }
+        move_vec
+    }
+
+    pub fn apply(&mut self, selected_move: Move) -> Result<(), MoveError> {
+        if self.result.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        if !self.legal_moves().contains(&selected_move) {
+            return Err(MoveError::IllegalMove);
+        }
+        self.play(selected_move);
+        Ok(())
+    }
+
+    // Plays a move without checking that it is legal
+    pub(crate) fn play(&mut self, selected_move: Move) {
+        let mut history_entry = HistoryEntry {
+            played_move: selected_move,
+            hand_index: 0,
+            last_moved_position: self.last_moved_position,
+            frozen_position: self.frozen_position,
+        };
+        match selected_move {
+            Move::Place { bug, destination } => {
+                let hand = &mut self.player_with_turn.hand;
+                let index = hand.iter().position(|piece| piece.bug == bug).unwrap();
+                history_entry.hand_index = index;
+                let piece = hand.remove(index);
+                self.board.push(destination, piece);
+                self.last_moved_position = Some(destination);
+                self.frozen_position = None;
+            }
+            Move::Move {
+                source,
+                destination,
+            } => {
+                let piece = self.board.pop(source);
+                self.board.push(destination, piece);
+                self.last_moved_position = Some(destination);
+                self.frozen_position = None;
+            }
+            Move::Throw {
+                source,
+                destination,
+                ..
+            } => {
+                let piece = self.board.pop(source);
+                self.board.push(destination, piece);
+                self.last_moved_position = Some(destination);
+                self.frozen_position = Some(destination);
+            }
+            Move::Pass => {
+                self.last_moved_position = None;
+                self.frozen_position = None;
+            }
+        }
+        self.history.push(history_entry);
+        self.advance_turn();
+        self.hash_history.push(self.hash());
+        self.result = self.check_for_game_result();
+    }
+
+    // Takes back the last move, returning it, or nothing at the start
+    pub fn undo(&mut self) -> Option<Move> {
+        let history_entry = self.history.pop()?;
+        self.hash_history.pop();
+        std::mem::swap(&mut self.player_with_turn, &mut self.player_without_turn);
+        self.player_with_turn.turns_taken -= 1;
+
+        match history_entry.played_move {
+            Move::Place { destination, .. } => {
+                let piece = self.board.pop(destination);
+                self.player_with_turn
+                    .hand
+                    .insert(history_entry.hand_index, piece);
+            }
+            Move::Move {
+                source,
+                destination,
+            }
+            | Move::Throw {
+                source,
+                destination,
+                ..
+            } => {
+                let piece = self.board.pop(destination);
+                self.board.push(source, piece);
+            }
+            Move::Pass => {}
+        }
+        self.last_moved_position = history_entry.last_moved_position;
+        self.frozen_position = history_entry.frozen_position;
+        // Nobody plays on after the game is over
+        self.result = None;
+        Some(history_entry.played_move)
+    }
+
+    fn advance_turn(&mut self) {
+        self.player_with_turn.turns_taken += 1;
+        std::mem::swap(&mut self.player_with_turn, &mut self.player_without_turn);
+    }
+
+    // Hexs of the pieces the player with the turn controls
+    fn find_movable_positions(&self) -> Vec<Hex> {
+        let mut position_vec = vec![];
+
+        // Nothing may move until the queen is on the board
+        if self.player_with_turn.has_queen_in_hand() {
+            return position_vec;
+        }
+
+        for position in self.board.occupied_positions() {
+            if self.board.top(position).player != self.player_with_turn.number {
+                continue;
+            }
+            if self.frozen_position == Some(position) {
+                continue;
+            }
+            position_vec.push(position);
+        }
+        position_vec
+    }
+
+    fn find_placeable_positions(&self) -> Vec<Hex> {
+        let occupied_position_vec = self.board.occupied_positions();
+
+        // Handles the first turn for each player where they have no existing
+        // pieces to play off of
+        match occupied_position_vec.as_slice() {
+            [] => return vec![Hex::ORIGIN],
+            [position] => return position.neighbors().to_vec(),
+            _ => {}
+        }
+
+        let mut placeable_position_vec: Vec<Hex> = vec![];
+        for occupied_position in occupied_position_vec {
+            for position in occupied_position.neighbors() {
+                if self.board.is_occupied(position) || placeable_position_vec.contains(&position) {
+                    continue;
+                }
+
+                // Only the piece on top of a stack decides who it counts for
+                let mut neighboring_piece_from_another_player = false;
+                for neighbor in self.board.neighboring_stacks(position) {
+                    let neighbor = neighbor.top();
+                    if neighbor.player != self.player_with_turn.number
+                        && neighbor.player != PlayerNumber::None
+                    {
+                        neighboring_piece_from_another_player = true;
+                    }
+                }
+                if neighboring_piece_from_another_player {
+                    continue;
+                }
+
+                placeable_position_vec.push(position);
+            }
+        }
+        placeable_position_vec
+    }
+
+    // The game is over once a queen has a piece on every side of it
+    fn check_for_game_result(&self) -> Option<GameResult> {
+        let player_one_surrounded = check_for_surrounded_queen(&self.board, PlayerNumber::One);
+        let player_two_surrounded = check_for_surrounded_queen(&self.board, PlayerNumber::Two);
+        match (player_one_surrounded, player_two_surrounded) {
+            (true, true) => Some(GameResult::Draw),
+            (true, false) => Some(GameResult::PlayerTwoWins),
+            (false, true) => Some(GameResult::PlayerOneWins),
+            (false, false) if self.check_for_threefold_repetition() => Some(GameResult::Draw),
+            (false, false) => None,
+        }
+    }
+
+    fn check_for_threefold_repetition(&self) -> bool {
+        if !self.repetition_draw {
+            return false;
+        }
+        let hash = self.hash();
+        self.hash_history
+            .iter()
+            .filter(|previous_hash| **previous_hash == hash)
+            .count()
+            >= 3
+    }
+}
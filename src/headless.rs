@@ -0,0 +1,121 @@
+// Headless games for scripts. Moves come in one per line on stdin, either in
+// notation like "bG1 wQ-" or as axial coordinates like "0,-1 -> 1,-1". Once
+// stdin runs out the board is printed and the exit code tells how the game
+// stands.
+
+use std::io::BufRead;
+
+use hive::{GameResult, GameState, Hex, Move};
+
+use crate::render;
+
+const EXIT_IN_PROGRESS: i32 = 0;
+const EXIT_PLAYER_ONE_WINS: i32 = 1;
+const EXIT_PLAYER_TWO_WINS: i32 = 2;
+const EXIT_DRAW: i32 = 3;
+// A line couldn't be read or wasn't a legal move
+const EXIT_INVALID_MOVE: i32 = 4;
+// The arguments were wrong or the game to play on couldn't be loaded, so no
+// move was read. Every mode exits with this when it can't start.
+pub const EXIT_USAGE: i32 = 5;
+// Characters each column of the board takes up when printed
+const CELL_WIDTH: usize = 4;
+
+// Gives the exit code
+pub fn run(mut game_state: GameState) -> i32 {
+    let stdin = std::io::stdin();
+    for (i, line) in stdin.lock().lines().enumerate() {
+        let Ok(line) = line else {
+            break;
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if game_state.result().is_some() {
+            eprintln!("Line {}: The game is over", i + 1);
+            print_game(&game_state);
+            return EXIT_INVALID_MOVE;
+        }
+        let played_move = match parse_line(&game_state, line) {
+            Ok(played_move) => played_move,
+            Err(message) => {
+                eprintln!("Line {}: {}: {}", i + 1, line, message);
+                print_game(&game_state);
+                return EXIT_INVALID_MOVE;
+            }
+        };
+        game_state
+            .apply(played_move)
+            .expect("Parsed moves are legal");
+    }
+
+    print_game(&game_state);
+    match game_state.result() {
+        Some(GameResult::PlayerOneWins) => EXIT_PLAYER_ONE_WINS,
+        Some(GameResult::PlayerTwoWins) => EXIT_PLAYER_TWO_WINS,
+        Some(GameResult::Draw) => EXIT_DRAW,
+        None => EXIT_IN_PROGRESS,
+    }
+}
+
+fn parse_line(game_state: &GameState, line: &str) -> Result<Move, String> {
+    let Some((source_string, destination_string)) = line.split_once("->") else {
+        return game_state
+            .parse_move(line)
+            .map_err(|notation_error| notation_error.to_string());
+    };
+    let source = parse_hex(source_string.trim())?;
+    let destination = parse_hex(destination_string.trim())?;
+    if !game_state.board().is_occupied(source) {
+        return Err(format!("Nothing at {}", source_string.trim()));
+    }
+    game_state
+        .find_move_between(source, destination)
+        .map_err(|notation_error| notation_error.to_string())
+}
+
+fn parse_hex(string: &str) -> Result<Hex, String> {
+    let coordinate_error = || format!("Coordinates look like q,r but got {}", string);
+    let (q, r) = string.split_once(',').ok_or_else(coordinate_error)?;
+    let q = q.trim().parse().map_err(|_| coordinate_error())?;
+    let r = r.trim().parse().map_err(|_| coordinate_error())?;
+    Ok(Hex::new(q, r))
+}
+
+////////////////////////////////////////////////////////////////////////
+
+// The board is drawn in the same doubled rows as the terminal game, with
+// every piece in notation and the height of a stack after its top piece
+fn print_game(game_state: &GameState) {
+    let board = game_state.board();
+    for cell_vec in render::find_board_rows(board) {
+        let mut line = String::new();
+        for (j, cell) in cell_vec.into_iter().enumerate() {
+            let Some(position) = cell else {
+                continue;
+            };
+            let stack = board.stack(position);
+            let cell_string = match stack.height() {
+                0 => ".".to_string(),
+                1 => stack.top().to_string(),
+                height => format!("{}({})", stack.top(), height),
+            };
+            // Long cells spill into the blank column next to them
+            while line.len() < j * CELL_WIDTH {
+                line.push(' ');
+            }
+            line.push_str(&cell_string);
+        }
+        println!("{}", line);
+    }
+
+    let status = match game_state.result() {
+        Some(GameResult::PlayerOneWins) => "WhiteWins",
+        Some(GameResult::PlayerTwoWins) => "BlackWins",
+        Some(GameResult::Draw) => "Draw",
+        None => "InProgress",
+    };
+    println!("{}", status);
+}
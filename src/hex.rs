@@ -0,0 +1,111 @@
+use std::ops::{Add, Sub};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    Northeast,
+    Southeast,
+    South,
+    Southwest,
+    Northwest,
+}
+
+pub const DIRECTION_ARR: [Direction; 6] = [
+    Direction::North,
+    Direction::Northeast,
+    Direction::Southeast,
+    Direction::South,
+    Direction::Southwest,
+    Direction::Northwest,
+];
+
+impl Direction {
+    // Turns clockwise by the given number of sixths of a turn
+    pub fn rotate(&self, steps: usize) -> Direction {
+        let index = DIRECTION_ARR
+            .iter()
+            .position(|direction| direction == self)
+            .unwrap();
+        DIRECTION_ARR[(index + steps) % DIRECTION_ARR.len()]
+    }
+
+    // The step from a hex to its neighbor in this direction
+    pub fn offset(&self) -> Hex {
+        match self {
+            Direction::North => Hex::new(0, -1),
+            Direction::Northeast => Hex::new(1, -1),
+            Direction::Southeast => Hex::new(1, 0),
+            Direction::South => Hex::new(0, 1),
+            Direction::Southwest => Hex::new(-1, 1),
+            Direction::Northwest => Hex::new(-1, 0),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+// Axial coordinates for flat topped hexes. The third cube coordinate is
+// always -q - r so it is never stored.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex { q: 0, r: 0 };
+
+    pub const fn new(q: i32, r: i32) -> Self {
+        Hex { q, r }
+    }
+
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    pub fn neighbor(&self, direction: Direction) -> Hex {
+        *self + direction.offset()
+    }
+
+    pub fn neighbors(&self) -> [Hex; 6] {
+        DIRECTION_ARR.map(|direction| self.neighbor(direction))
+    }
+
+    // Number of steps between the two hexes
+    pub fn distance(&self, other: Hex) -> usize {
+        let difference = *self - other;
+        let steps = difference.q.abs() + difference.r.abs() + difference.s().abs();
+        (steps / 2) as usize
+    }
+
+    pub fn direction_to(&self, neighbor: Hex) -> Option<Direction> {
+        DIRECTION_ARR
+            .into_iter()
+            .find(|direction| self.neighbor(*direction) == neighbor)
+    }
+
+    // Turns clockwise around the origin by the given number of sixths of a turn
+    pub fn rotate(&self, steps: usize) -> Hex {
+        let mut hex = *self;
+        for _ in 0..steps % DIRECTION_ARR.len() {
+            hex = Hex::new(-hex.r, -hex.s());
+        }
+        hex
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, other: Hex) -> Hex {
+        Hex::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, other: Hex) -> Hex {
+        Hex::new(self.q - other.q, self.r - other.r)
+    }
+}
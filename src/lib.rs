@@ -0,0 +1,21 @@
+// Rules engine for Hive. Everything here is pure game logic so it can be
+// driven by the terminal UI, bots or tests alike.
+
+pub mod ai;
+pub mod board;
+pub mod game_state;
+pub mod hex;
+pub mod notation;
+mod perft;
+pub mod piece;
+pub mod record;
+mod rules;
+pub mod zobrist;
+
+pub use ai::{Ai, Budget, Strategy};
+pub use board::{Board, Stack};
+pub use game_state::{GameResult, GameState, Move, MoveError, Player};
+pub use hex::{Direction, Hex, DIRECTION_ARR};
+pub use notation::NotationError;
+pub use piece::{Bug, Expansions, Piece, PlayerNumber};
+pub use record::{GameRecord, RecordError};
@@ -1,23 +1,152 @@
+mod headless;
+mod render;
+mod uhp;
+
 use colored::Colorize;
 use console::Term;
-use std::collections::HashSet;
-use std::fmt;
+use hive::{
+    Ai, Budget, Bug, Expansions, GameRecord, GameResult, GameState, Hex, Move, Piece, Player,
+    PlayerNumber, RecordError, Stack, Strategy,
+};
+use std::time::{Duration, Instant};
 
-const BOARD_SIZE: usize = 40;
-const FIRST_LOCATION: (usize, usize) = (BOARD_SIZE / 2, BOARD_SIZE / 2);
 const ADVANCE_KEY: char = 'e';
 const BACK_KEY: char = 'q';
 // const UP_KEY: char = 'w';
 const LEFT_KEY: char = 'a';
 // const DOWN_KEY: char = 's';
 const RIGHT_KEY: char = 'd';
+const ABILITY_KEY: char = 'r';
+const UNDO_KEY: char = 'z';
+const REDO_KEY: char = 'x';
+const MOSQUITO_ARG: &str = "--mosquito";
+const LADYBUG_ARG: &str = "--ladybug";
+const PILLBUG_ARG: &str = "--pillbug";
+const UHP_ARG: &str = "--uhp";
+const LOAD_ARG: &str = "--load";
+const SAVE_ARG: &str = "--save";
+const REPLAY_ARG: &str = "--replay";
+const PLAYER2_ARG: &str = "--player2";
+const AI_TIME_ARG: &str = "--ai-time";
+const AI_ITERATIONS_ARG: &str = "--ai-iterations";
+const AI_DEPTH_ARG: &str = "--ai-depth";
+const NO_REPETITION_DRAW_ARG: &str = "--no-repetition-draw";
+const PERFT_ARG: &str = "--perft";
+const HEADLESS_ARG: &str = "--headless";
+// Milliseconds the computer gets per move unless told otherwise
+const DEFAULT_AI_TIME: u64 = 2000;
+// Unless told otherwise every game is written after every move to a new file
+// named like hive-3.pgn, so earlier games are never overwritten
+const RECORD_FILE_PREFIX: &str = "hive-";
+const RECORD_FILE_EXTENSION: &str = "pgn";
 
 /////////////////////////////////////////////////////////////////////////
 
 fn main() {
-    let mut game = Game::new();
-    game.print();
+    let mut expansions = Expansions::default();
+    let mut uhp = false;
+    let mut headless = false;
+    let mut load_path = None;
+    let mut save_path = None;
+    let mut replay_path = None;
+    let mut strategy = None;
+    let mut budget = Budget::Time(Duration::from_millis(DEFAULT_AI_TIME));
+    let mut repetition_draw = true;
+    let mut perft_depth = None;
+    let mut arg_iter = std::env::args().skip(1);
+    while let Some(arg) = arg_iter.next() {
+        match arg.as_str() {
+            MOSQUITO_ARG => expansions.mosquito = true,
+            LADYBUG_ARG => expansions.ladybug = true,
+            PILLBUG_ARG => expansions.pillbug = true,
+            UHP_ARG => uhp = true,
+            HEADLESS_ARG => headless = true,
+            NO_REPETITION_DRAW_ARG => repetition_draw = false,
+            LOAD_ARG | SAVE_ARG | REPLAY_ARG => {
+                let Some(path) = arg_iter.next() else {
+                    eprintln!("{} needs a file", arg);
+                    std::process::exit(headless::EXIT_USAGE);
+                };
+                match arg.as_str() {
+                    LOAD_ARG => load_path = Some(path),
+                    SAVE_ARG => save_path = Some(path),
+                    _ => replay_path = Some(path),
+                }
+            }
+            PLAYER2_ARG => match arg_iter.next().as_deref() {
+                Some("human") => strategy = None,
+                Some("ai") => strategy = Some(Strategy::AlphaBeta),
+                Some("mcts") => strategy = Some(Strategy::Mcts),
+                _ => {
+                    eprintln!("{} is one of human, ai or mcts", arg);
+                    std::process::exit(headless::EXIT_USAGE);
+                }
+            },
+            PERFT_ARG => {
+                let Some(depth) = arg_iter.next().and_then(|depth| depth.parse().ok()) else {
+                    eprintln!("{} needs a depth", arg);
+                    std::process::exit(headless::EXIT_USAGE);
+                };
+                perft_depth = Some(depth);
+            }
+            AI_TIME_ARG | AI_ITERATIONS_ARG | AI_DEPTH_ARG => {
+                let Some(amount) = arg_iter.next().and_then(|amount| amount.parse().ok()) else {
+                    eprintln!("{} needs a number", arg);
+                    std::process::exit(headless::EXIT_USAGE);
+                };
+                budget = match arg.as_str() {
+                    AI_TIME_ARG => Budget::Time(Duration::from_millis(amount)),
+                    AI_ITERATIONS_ARG => Budget::Iterations(amount as usize),
+                    _ => Budget::Depth(amount as usize),
+                };
+            }
+            _ => {
+                eprintln!("Unknown argument {}", arg);
+                std::process::exit(headless::EXIT_USAGE);
+            }
+        }
+    }
+
+    // The game type comes from newgame instead of the arguments
+    if uhp {
+        uhp::run();
+        return;
+    }
+
+    // A loaded game keeps being saved to the file it came from, while a
+    // replayed one is only read
+    let mut record = match replay_path.as_ref().or(load_path.as_ref()) {
+        Some(path) => match load_record(path) {
+            Ok(record) => record,
+            Err(message) => {
+                eprintln!("Could not load {}: {}", path, message);
+                std::process::exit(headless::EXIT_USAGE);
+            }
+        },
+        None => GameRecord::new(expansions),
+    };
+    // A loaded game played without repetition draws keeps playing without them
+    if !repetition_draw {
+        record.repetition_draw = false;
+    }
+    if let Some(depth) = perft_depth {
+        run_perft(&record, depth);
+        return;
+    }
+    // Moves come from stdin on top of a loaded game, which is left unchanged
+    if headless {
+        std::process::exit(headless::run(record.replay()));
+    }
+    let mut game = if replay_path.is_some() {
+        Game::new_replay(record)
+    } else {
+        let record_path = save_path.or(load_path).unwrap_or_else(find_new_record_path);
+        Game::new(record, Some(record_path))
+    };
+    game.ai = strategy.map(|strategy| Ai::new(strategy, budget));
     game.update();
+    game.print();
+    game.play_ai_turn();
 
     let stdout = Term::buffered_stdout();
 
@@ -25,14 +154,34 @@ fn main() {
         if let Ok(character) = stdout.read_char() {
             match game.state {
                 State::SelectPiece => match character {
+                    UNDO_KEY => {
+                        game.undo();
+                    }
+                    REDO_KEY => {
+                        game.redo();
+                    }
                     LEFT_KEY => {
                         game.move_piece_cursor(MoveDirection::Previous);
                     }
                     RIGHT_KEY => {
                         game.move_piece_cursor(MoveDirection::Next);
                     }
+                    // A pillbug that can only throw goes straight to its throws
                     ADVANCE_KEY => {
-                        game.state = State::SelectPlacingLocation;
+                        if !game.piece_destination_vec.is_empty() {
+                            game.piece_destination_vec_index = 0;
+                            game.state = State::SelectPlacingLocation;
+                        } else if !game.thrown_piece_vec.is_empty() {
+                            game.state = State::SelectThrownPiece;
+                        } else {
+                            continue;
+                        }
+                    }
+                    ABILITY_KEY => {
+                        if game.thrown_piece_vec.is_empty() {
+                            continue;
+                        }
+                        game.state = State::SelectThrownPiece;
                     }
                     _ => continue,
                 },
@@ -44,6 +193,9 @@ fn main() {
                         game.move_location_cursor(MoveDirection::Next);
                     }
                     ADVANCE_KEY => {
+                        if game.piece_destination_vec.is_empty() {
+                            continue;
+                        }
                         game.state = State::ConfirmPlacingLocation;
                     }
                     BACK_KEY => {
@@ -53,134 +205,179 @@ fn main() {
                 },
                 State::ConfirmPlacingLocation => match character {
                     ADVANCE_KEY => {
-                        game.place_selected_piece();
-                        game.advance_turn();
-                        game.clear_selections();
-                        game.state = State::SelectPiece;
+                        game.play_selected_move();
                     }
                     BACK_KEY => {
                         game.state = State::SelectPlacingLocation;
                     }
                     _ => continue,
                 },
+                State::SelectThrownPiece => match character {
+                    LEFT_KEY => {
+                        game.move_thrown_piece_cursor(MoveDirection::Previous);
+                    }
+                    RIGHT_KEY => {
+                        game.move_thrown_piece_cursor(MoveDirection::Next);
+                    }
+                    ADVANCE_KEY => {
+                        game.piece_destination_vec_index = 0;
+                        game.state = State::SelectThrowingLocation;
+                    }
+                    BACK_KEY => {
+                        game.state = State::SelectPiece;
+                    }
+                    _ => continue,
+                },
+                State::SelectThrowingLocation => match character {
+                    LEFT_KEY => {
+                        game.move_location_cursor(MoveDirection::Previous);
+                    }
+                    RIGHT_KEY => {
+                        game.move_location_cursor(MoveDirection::Next);
+                    }
+                    ADVANCE_KEY => {
+                        game.state = State::ConfirmThrowingLocation;
+                    }
+                    BACK_KEY => {
+                        game.state = State::SelectThrownPiece;
+                    }
+                    _ => continue,
+                },
+                State::ConfirmThrowingLocation => match character {
+                    ADVANCE_KEY => {
+                        game.play_selected_move();
+                    }
+                    BACK_KEY => {
+                        game.state = State::SelectThrowingLocation;
+                    }
+                    _ => continue,
+                },
+                State::MustPass => match character {
+                    ADVANCE_KEY => {
+                        game.play_selected_move();
+                    }
+                    UNDO_KEY => {
+                        game.undo();
+                    }
+                    _ => continue,
+                },
+                State::Replay => match character {
+                    LEFT_KEY => {
+                        game.undo();
+                    }
+                    RIGHT_KEY => {
+                        game.redo();
+                    }
+                    BACK_KEY => {
+                        return;
+                    }
+                    _ => continue,
+                },
+                State::GameOver(_) => match character {
+                    UNDO_KEY => {
+                        game.undo();
+                    }
+                    ADVANCE_KEY => {
+                        let mut record = GameRecord::new(game.game_state.expansions());
+                        record.repetition_draw = game.record.repetition_draw;
+                        let ai = game.ai;
+                        // The finished game stays in its own file
+                        let record_path = game.record_path.map(|_| find_new_record_path());
+                        game = Game::new(record, record_path);
+                        game.ai = ai;
+                    }
+                    BACK_KEY => {
+                        return;
+                    }
+                    _ => continue,
+                },
             }
             game.update();
             game.print();
+            game.play_ai_turn();
         }
     }
 }
 
 /////////////////////////////////////////////////////////////////////////
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-enum Bug {
-    None,
-    Grasshopper,
-    Spider,
-    Ant,
-    Queen,
-    Beetle,
-    // Mosquito,
-    // Pillbug,
-    // Ladybug,
-}
-
-impl fmt::Display for Bug {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Bug::None => write!(f, " "),
-            Bug::Grasshopper => write!(f, "G"),
-            Bug::Spider => write!(f, "S"),
-            Bug::Ant => write!(f, "A"),
-            Bug::Queen => write!(f, "Q"),
-            Bug::Beetle => write!(f, "B"),
-        }
+// Counts every depth up to the one asked for, starting from a loaded game or
+// the opening
+fn run_perft(record: &GameRecord, depth: usize) {
+    let game_state = record.replay();
+    for depth in 1..=depth {
+        let start = Instant::now();
+        let leaf_count = game_state.perft(depth);
+        println!(
+            "perft({}) = {} in {:.3}s",
+            depth,
+            leaf_count,
+            start.elapsed().as_secs_f64()
+        );
     }
 }
 
-/////////////////////////////////////////////////////////////////////////
-
-#[derive(Debug, Copy, Clone, PartialEq)]
-enum PlayerNumber {
-    None,
-    One,
-    Two,
-}
-
-impl fmt::Display for PlayerNumber {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            PlayerNumber::One => write!(f, "1"),
-            PlayerNumber::Two => write!(f, "2"),
-            PlayerNumber::None => write!(f, "!"),
-        }
-    }
+// The first numbered record file that doesn't exist yet
+fn find_new_record_path() -> String {
+    (1..)
+        .map(|number| format!("{}{}.{}", RECORD_FILE_PREFIX, number, RECORD_FILE_EXTENSION))
+        .find(|path| !std::path::Path::new(path).exists())
+        .expect("Some record file number is free")
 }
 
-#[derive(Debug, Clone)]
-struct Player {
-    number: PlayerNumber,
-    hand: Vec<Piece>,
+fn load_record(path: &str) -> Result<GameRecord, String> {
+    let record_string = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+    record_string
+        .parse()
+        .map_err(|error: RecordError| error.to_string())
 }
 
-impl Player {
-    fn new(number: PlayerNumber) -> Self {
-        Player {
-            number,
-            hand: create_hand(number),
-        }
-    }
-
-    fn get_hand_selection_vec(&self) -> Vec<Selection> {
-        let mut hand_selection_vec: Vec<Selection> = vec![];
+/////////////////////////////////////////////////////////////////////////
 
-        for (i, _piece) in self.hand.iter().enumerate() {
-            hand_selection_vec.push(Selection {
-                location: Location::Hand,
-                row: 0,
-                col: i,
-            })
-        }
-        hand_selection_vec
-    }
-
-    fn print_hand(&self, selection: usize, show_selection: bool) {
-        print!("                                                  ");
-        for (i, piece) in self.hand.iter().enumerate() {
-            let mut selected = i == selection;
-            selected &= show_selection;
-            piece.print(selected);
-        }
-        println!();
+fn print_hand(player: &Player, selection: usize, show_selection: bool) {
+    print!("                                                  ");
+    for (i, piece) in player.hand.iter().enumerate() {
+        let mut selected = i == selection;
+        selected &= show_selection;
+        print_piece(piece, selected);
     }
+    println!();
 }
 
-/////////////////////////////////////////////////////////////////////////
+fn print_piece(piece: &Piece, selected: bool) {
+    let piece_string = format!("{}", piece.bug);
+    let piece_string_colored = match piece.player {
+        PlayerNumber::One => piece_string.blue(),
+        PlayerNumber::Two => piece_string.red(),
+        _ => piece_string.white(),
+    };
 
-#[derive(Debug, Copy, Clone)]
-struct Piece {
-    bug: Bug,
-    player: PlayerNumber,
+    if selected {
+        print!("|{}|", piece_string_colored);
+    } else {
+        print!(" {} ", piece_string_colored);
+    }
 }
 
-impl Piece {
-    fn print(&self, selected: bool) {
-        let piece_string = format!("{}", self.bug);
-        let piece_string_colored = match self.player {
-            PlayerNumber::One => piece_string.blue(),
-            PlayerNumber::Two => piece_string.red(),
-            _ => piece_string.white(),
-        };
-
-        if selected {
-            print!("|{}|", piece_string_colored);
-        } else {
-            print!(" {} ", piece_string_colored);
-        }
+// Stacks show their height next to the top piece
+fn print_stack(stack: &Stack, selected: bool) {
+    if stack.height() <= 1 {
+        print_piece(&stack.top(), selected);
+        return;
     }
 
-    fn new(bug: Bug, player: PlayerNumber) -> Self {
-        Piece { bug, player }
+    let piece = stack.top();
+    let piece_string = format!("{}{}", piece.bug, stack.height());
+    let piece_string_colored = match piece.player {
+        PlayerNumber::One => piece_string.blue(),
+        PlayerNumber::Two => piece_string.red(),
+        _ => piece_string.white(),
+    };
+
+    if selected {
+        print!("|{}", piece_string_colored);
+    } else {
+        print!(" {}", piece_string_colored);
     }
 }
 
@@ -194,330 +391,27 @@ enum Location {
 }
 
 #[derive(Hash, Eq, PartialEq, Debug, Copy, Clone)]
+// Board selections are drawn in doubled rows, so north and south are two
+// rows away and every other neighbor is one row and one column away
 struct Selection {
     location: Location,
-    row: usize,
-    col: usize,
-}
-
-#[derive(Debug, Copy, Clone, PartialEq)]
-enum Direction {
-    North,
-    Northeast,
-    Southeast,
-    South,
-    Southwest,
-    Northwest,
-}
-
-const DIRECTION_ARR: [Direction; 6] = [
-    Direction::North,
-    Direction::Northeast,
-    Direction::Southeast,
-    Direction::South,
-    Direction::Southwest,
-    Direction::Northwest,
-];
-
-#[derive(Debug, Copy, Clone, PartialEq)]
-enum FindError {
-    NotFound,
-}
-
-fn move_selection(selection: Selection, direction: Direction) -> Result<Selection, Selection> {
-    let mut moved_selection = selection.clone();
-    if moved_selection.row < 2 || moved_selection.col < 2 {
-        return Err(moved_selection);
-    }
-    match direction {
-        Direction::North => {
-            moved_selection.row -= 2;
-        }
-        Direction::Northeast => {
-            moved_selection.row -= 1;
-            moved_selection.col += 1;
-        }
-        Direction::Southeast => {
-            moved_selection.row += 1;
-            moved_selection.col += 1;
-        }
-        Direction::South => {
-            moved_selection.row += 2;
-        }
-        Direction::Southwest => {
-            moved_selection.row += 1;
-            moved_selection.col -= 1;
-        }
-        Direction::Northwest => {
-            moved_selection.row -= 1;
-            moved_selection.col -= 1;
-        }
-    }
-    Ok(moved_selection)
-}
-
-fn find_grasshopper_movable_location(
-    board: [[Piece; BOARD_SIZE]; BOARD_SIZE],
-    selection: Selection,
-) -> Vec<Selection> {
-    let mut location_vec = vec![];
-    for direction in DIRECTION_ARR {
-        let result = test_grasshopper_direction(board, direction, selection);
-        if result.is_ok() {
-            location_vec.push(result.unwrap());
-        }
-    }
-    location_vec
-}
-
-fn test_grasshopper_direction(
-    board: [[Piece; BOARD_SIZE]; BOARD_SIZE],
-    direction: Direction,
-    selection: Selection,
-) -> Result<Selection, FindError> {
-    let starting_selection = move_selection(selection, direction).unwrap();
-    let mut current_selection = starting_selection;
-    loop {
-        if current_selection.row >= board.len() || current_selection.col >= board.len() {
-            break;
-        }
-        let current_player = board[current_selection.row][current_selection.col].player;
-        // println!("{}, {}, {}", i, j, current_player);
-        if current_player == PlayerNumber::None {
-            if current_selection.row != starting_selection.row
-                || current_selection.col != starting_selection.col
-            {
-                return Ok(Selection {
-                    location: Location::Board,
-                    row: current_selection.row,
-                    col: current_selection.col,
-                });
-            }
-            break;
-        }
-        let move_result = move_selection(current_selection, direction);
-        if move_result.is_err() {
-            break;
-        }
-        current_selection = move_result.unwrap();
-    }
-    Err(FindError::NotFound)
-}
-
-fn find_slide_locations(
-    board: [[Piece; BOARD_SIZE]; BOARD_SIZE],
-    selection: Selection,
-    slides: i32,
-) -> Vec<Selection> {
-    let valid_location_vec = find_ant_locations(board, selection);
-    let mut traversed_vec = vec![selection.clone()];
-    let mut current_vec = traversed_vec.clone();
-
-    for _ in 1..=slides {
-        let clone_vec = current_vec.clone();
-        current_vec.clear();
-        for current_selection in clone_vec {
-            for direction in DIRECTION_ARR {
-                let result = move_selection(current_selection, direction);
-                if result.is_err() {
-                    continue;
-                }
-                let moved_selection = result.unwrap();
-                if valid_location_vec.contains(&moved_selection)
-                    && !traversed_vec.contains(&moved_selection)
-                {
-                    current_vec.push(moved_selection);
-                    traversed_vec.push(moved_selection);
-                }
-            }
-        }
-    }
-
-    current_vec
-}
-
-fn find_ant_locations(
-    board: [[Piece; BOARD_SIZE]; BOARD_SIZE],
-    selection: Selection,
-) -> Vec<Selection> {
-    let mut board_clone = board.clone();
-    board_clone[selection.row][selection.col] = Piece::new(Bug::None, PlayerNumber::None);
-
-    let mut location_vec = vec![];
-    for (i, row) in board_clone.iter().enumerate() {
-        for (j, _piece) in row.iter().enumerate() {
-            if check_for_occupied_location(board_clone, i, j) {
-                continue;
-            }
-
-            if !check_for_neighboring_piece(board_clone, i, j) {
-                continue;
-            }
-
-            if !check_for_slide_in(board_clone, i, j) {
-                continue;
-            }
-
-            location_vec.push(Selection {
-                location: Location::Board,
-                row: i,
-                col: j,
-            })
-        }
-    }
-    location_vec
-}
-
-fn find_queen_locations(
-    board: [[Piece; BOARD_SIZE]; BOARD_SIZE],
-    selection: Selection,
-) -> Vec<Selection> {
-    let ant_location_vec = find_ant_locations(board, selection);
-    let mut location_vec = vec![];
-    for direction in DIRECTION_ARR {
-        let result = move_selection(selection, direction);
-        if result.is_ok() {
-            let current_selection = result.unwrap();
-            if ant_location_vec.contains(&current_selection) {
-                location_vec.push(current_selection);
-            }
-        }
-    }
-    location_vec
-}
-
-fn get_neighboring_piece_vec(
-    board: [[Piece; BOARD_SIZE]; BOARD_SIZE],
-    row: usize,
-    col: usize,
-) -> Vec<Piece> {
-    let mut neighboring_piece_vec: Vec<Piece> = vec![];
-    // North
-    if row >= 2 {
-        neighboring_piece_vec.push(board[row - 2][col])
-    }
-    // Northwest
-    if row >= 1 && col >= 1 {
-        neighboring_piece_vec.push(board[row - 1][col - 1])
-    }
-    // Northeast
-    if row >= 1 && col <= board.len() - 2 {
-        neighboring_piece_vec.push(board[row - 1][col + 1])
-    }
-    // Southwest
-    if row <= board.len() - 2 && col >= 1 {
-        neighboring_piece_vec.push(board[row + 1][col - 1])
-    }
-    // Southeast
-    if row <= board.len() - 2 && col <= board.len() - 2 {
-        neighboring_piece_vec.push(board[row + 1][col + 1])
-    }
-    // South
-    if row <= board.len() - 3 {
-        neighboring_piece_vec.push(board[row + 2][col])
-    }
-    return neighboring_piece_vec;
-}
-
-//////////////////////////////////////////////////////////////////////
-/// Rules
-//////////////////////////////////////////////////////////////////////
-
-fn check_for_neighboring_piece(
-    board: [[Piece; BOARD_SIZE]; BOARD_SIZE],
-    row: usize,
-    col: usize,
-) -> bool {
-    let neighboring_piece_vec = get_neighboring_piece_vec(board, row, col);
-    let mut neighboring_piece = false;
-    for neighbor in neighboring_piece_vec {
-        if neighbor.player != PlayerNumber::None {
-            neighboring_piece = true;
-        }
-    }
-    neighboring_piece
+    row: i32,
+    col: i32,
 }
 
-fn check_for_slide_in(board: [[Piece; BOARD_SIZE]; BOARD_SIZE], row: usize, col: usize) -> bool {
-    let neighboring_piece_vec = get_neighboring_piece_vec(board, row, col);
-    let mut neighboring_piece_count = 0;
-    for neighbor in neighboring_piece_vec {
-        if neighbor.player != PlayerNumber::None {
-            neighboring_piece_count += 1;
-        }
-    }
-    let slide_in: bool = neighboring_piece_count <= 4;
-    slide_in
-}
-
-fn check_for_occupied_location(
-    board: [[Piece; BOARD_SIZE]; BOARD_SIZE],
-    row: usize,
-    col: usize,
-) -> bool {
-    let occupied_location = board[row][col].player != PlayerNumber::None;
-    occupied_location
-}
-
-// Recursive function to navigate the board and fill a set of connected pieces from the starting selection.
-// May bite me in the future but for now it seems to get the job done
-fn discover(
-    board: [[Piece; BOARD_SIZE]; BOARD_SIZE],
-    selection: Selection,
-    set: &mut HashSet<Selection>,
-) {
-    // println!("Called {} {}", selection.row, selection.col);
-    set.insert(selection);
-    for direction in DIRECTION_ARR {
-        let result = move_selection(selection, direction);
-        if result.is_ok() {
-            let moved_selection = result.unwrap();
-            if !check_for_occupied_location(board, moved_selection.row, moved_selection.col) {
-                continue;
-            }
-            if set.contains(&Selection {
-                location: Location::Board,
-                row: moved_selection.row,
-                col: moved_selection.col,
-            }) {
-                continue;
-            }
-            discover(board, moved_selection, set);
+impl Selection {
+    fn from_hex(hex: Hex) -> Self {
+        let (row, col) = render::to_doubled(hex);
+        Selection {
+            location: Location::Board,
+            row,
+            col,
         }
     }
-}
 
-// FIXME
-fn check_for_broken_hive_if_empty(
-    board: [[Piece; BOARD_SIZE]; BOARD_SIZE],
-    row: usize,
-    col: usize,
-) -> bool {
-    let mut board_clone = board.clone();
-    board_clone[row][col] = Piece::new(Bug::None, PlayerNumber::None);
-
-    let mut occupied_locations = vec![];
-    for (i, row) in board_clone.iter().enumerate() {
-        for (j, _piece) in row.iter().enumerate() {
-            if check_for_occupied_location(board_clone, i, j) {
-                occupied_locations.push(Selection {
-                    location: Location::Board,
-                    row: i,
-                    col: j,
-                });
-            }
-        }
+    fn to_hex(self) -> Hex {
+        render::from_doubled(self.row, self.col)
     }
-
-    let mut occupied_location_set = HashSet::new();
-    discover(
-        board_clone,
-        occupied_locations[0],
-        &mut occupied_location_set,
-    );
-
-    let broken_hive = occupied_location_set.len() != occupied_locations.len();
-    broken_hive
 }
 
 ////////////////////////////////////////////////////////////////////////
@@ -526,61 +420,78 @@ enum MoveDirection {
     Previous,
 }
 
+// Cursor state for picking one of the engine's legal moves with the keyboard
 #[derive(Debug)]
 struct Game {
-    board: [[Piece; BOARD_SIZE]; BOARD_SIZE],
-    player_with_turn: Player,
-    player_without_turn: Player,
+    game_state: GameState,
+    record: GameRecord,
+    // Nothing is saved without a path
+    record_path: Option<String>,
+    // Undone moves, most recently undone last
+    redo_move_vec: Vec<Move>,
+    // The computer plays player two when there is one
+    ai: Option<Ai>,
+    legal_move_vec: Vec<Move>,
     state: State,
     piece_destination_vec_index: usize,
     piece_destination_vec: Vec<Selection>,
     piece_source_vec_index: usize,
     piece_source_vec: Vec<Selection>,
+    thrown_piece_vec_index: usize,
+    thrown_piece_vec: Vec<Selection>,
 }
 
 impl Game {
-    fn new() -> Self {
-        // TODO make this a 3d array
-        let board: [[Piece; BOARD_SIZE]; BOARD_SIZE] =
-            [[Piece::new(Bug::None, PlayerNumber::None); BOARD_SIZE]; BOARD_SIZE];
-        let player_with_turn = Player::new(PlayerNumber::One);
-        let player_without_turn = Player::new(PlayerNumber::Two);
-        let state = State::SelectPiece;
-        let piece_destination_vec_index: usize = 0;
-        let piece_destination_vec = vec![Selection {
-            location: Location::Board,
-            row: 0,
-            col: 0,
-        }];
-        let piece_source_vec_index: usize = 0;
-        let piece_source_vec = vec![Selection {
-            location: Location::Hand,
-            row: 0,
-            col: 0,
-        }];
-
+    fn new(record: GameRecord, record_path: Option<String>) -> Self {
+        let game_state = record.replay();
+        let state = match game_state.result() {
+            Some(game_result) => State::GameOver(game_result),
+            None => State::SelectPiece,
+        };
         Game {
-            board,
-            player_with_turn,
-            player_without_turn,
+            game_state,
+            record,
+            record_path,
+            redo_move_vec: vec![],
+            ai: None,
+            legal_move_vec: vec![],
             state,
-            piece_destination_vec_index,
-            piece_destination_vec,
-            piece_source_vec_index,
-            piece_source_vec,
+            piece_destination_vec_index: 0,
+            piece_destination_vec: vec![],
+            piece_source_vec_index: 0,
+            piece_source_vec: vec![],
+            thrown_piece_vec_index: 0,
+            thrown_piece_vec: vec![],
         }
     }
 
+    // Starts before the first move with every move of the record ready to redo
+    fn new_replay(mut record: GameRecord) -> Self {
+        let mut redo_move_vec = std::mem::take(&mut record.move_vec);
+        redo_move_vec.reverse();
+        let mut game = Game::new(record, None);
+        game.redo_move_vec = redo_move_vec;
+        game.state = State::Replay;
+        game
+    }
+
     fn clear_selections(&mut self) {
         self.piece_source_vec = vec![];
         self.piece_destination_vec = vec![];
+        self.thrown_piece_vec = vec![];
         self.piece_source_vec_index = 0;
         self.piece_destination_vec_index = 0;
+        self.thrown_piece_vec_index = 0;
     }
 
     fn update(&mut self) {
+        self.legal_move_vec = self.game_state.legal_moves();
         self.find_piece_sources();
+        self.find_thrown_pieces();
         self.find_piece_destinations();
+        if self.state == State::SelectPiece && self.legal_move_vec == [Move::Pass] {
+            self.state = State::MustPass;
+        }
     }
 
     fn get_piece_source(&self) -> Selection {
@@ -605,7 +516,22 @@ impl Game {
         self.piece_destination_vec[self.piece_destination_vec_index]
     }
 
+    fn get_thrown_piece(&self) -> Selection {
+        if self.thrown_piece_vec.is_empty() {
+            return Selection {
+                location: Location::None,
+                row: 0,
+                col: 0,
+            };
+        }
+        self.thrown_piece_vec[self.thrown_piece_vec_index]
+    }
+
     fn move_piece_cursor(&mut self, move_direction: MoveDirection) {
+        if self.piece_source_vec.is_empty() {
+            return;
+        }
+
         match move_direction {
             MoveDirection::Next => {
                 if self.piece_source_vec_index >= self.piece_source_vec.len() - 1 {
@@ -625,8 +551,9 @@ impl Game {
     }
 
     fn move_location_cursor(&mut self, move_direction: MoveDirection) {
-        // TODO Handle when player has nowhere to place
-        assert!(!self.piece_destination_vec.is_empty());
+        if self.piece_destination_vec.is_empty() {
+            return;
+        }
 
         match move_direction {
             MoveDirection::Next => {
@@ -646,198 +573,276 @@ impl Game {
         }
     }
 
-    // Not sure I like this function
-    fn place_selected_piece(&mut self) {
-        let selection = self.get_piece_source();
-        let piece_to_place = match selection.location {
-            Location::Board => self.board[selection.row][selection.col],
-            Location::Hand => self.player_with_turn.hand.remove(selection.col),
-            Location::None => {
-                panic!();
+    fn move_thrown_piece_cursor(&mut self, move_direction: MoveDirection) {
+        if self.thrown_piece_vec.is_empty() {
+            return;
+        }
+
+        match move_direction {
+            MoveDirection::Next => {
+                if self.thrown_piece_vec_index >= self.thrown_piece_vec.len() - 1 {
+                    self.thrown_piece_vec_index = 0;
+                } else {
+                    self.thrown_piece_vec_index += 1;
+                }
             }
-        };
-        let piece_destination = self.get_piece_destination();
-        self.board[selection.row][selection.col] = Piece::new(Bug::None, PlayerNumber::None);
-        self.board[piece_destination.row][piece_destination.col] = piece_to_place;
-        self.piece_source_vec_index = 0;
+            MoveDirection::Previous => {
+                if self.thrown_piece_vec_index == 0 {
+                    self.thrown_piece_vec_index = self.thrown_piece_vec.len() - 1
+                } else {
+                    self.thrown_piece_vec_index -= 1;
+                }
+            }
+        }
     }
 
-    fn advance_turn(&mut self) {
-        let temp_player = self.player_with_turn.clone();
-        self.player_with_turn = self.player_without_turn.clone();
-        self.player_without_turn = temp_player;
+    // Nothing is selected until there is a destination to go to
+    fn get_selected_move(&self) -> Option<Move> {
+        if self.state == State::MustPass {
+            return Some(Move::Pass);
+        }
+        let piece_source = self.get_piece_source();
+        let piece_destination = self.get_piece_destination();
+        if piece_source.location == Location::None || piece_destination.location == Location::None {
+            return None;
+        }
+        let piece_destination = piece_destination.to_hex();
+        let selected_move = match self.state {
+            State::ConfirmThrowingLocation => Move::Throw {
+                pillbug: piece_source.to_hex(),
+                source: self.get_thrown_piece().to_hex(),
+                destination: piece_destination,
+            },
+            _ if piece_source.location == Location::Hand => Move::Place {
+                bug: self.game_state.player_with_turn().hand[piece_source.col as usize].bug,
+                destination: piece_destination,
+            },
+            _ => Move::Move {
+                source: piece_source.to_hex(),
+                destination: piece_destination,
+            },
+        };
+        Some(selected_move)
     }
 
-    fn get_board_selections(&mut self) -> Vec<Selection> {
-        let mut board_selection_vec = vec![];
+    fn play_selected_move(&mut self) {
+        if let Some(selected_move) = self.get_selected_move() {
+            self.play_move(selected_move);
+        }
+    }
 
-        for (i, row) in self.board.iter().enumerate() {
-            for (j, piece) in row.iter().enumerate() {
-                if piece.player != self.player_with_turn.number {
-                    continue;
-                }
+    fn play_move(&mut self, selected_move: Move) {
+        self.game_state
+            .apply(selected_move)
+            .expect("Only legal moves can be selected");
+        self.record.move_vec.push(selected_move);
+        self.redo_move_vec.clear();
+        self.finish_move();
+    }
 
-                if check_for_broken_hive_if_empty(self.board, i, j) {
-                    continue;
-                }
+    fn undo(&mut self) {
+        let Some(undone_move) = self.record.move_vec.pop() else {
+            return;
+        };
+        self.game_state.undo();
+        self.redo_move_vec.push(undone_move);
+        self.finish_move();
 
-                board_selection_vec.push(Selection {
-                    location: Location::Board,
-                    row: i,
-                    col: j,
-                });
-            }
+        // Against the computer a whole round is taken back
+        if self.ai_has_turn() {
+            self.undo();
         }
-        board_selection_vec
     }
 
-    fn find_piece_sources(&mut self) {
-        let mut piece_source_vec = self.player_with_turn.get_hand_selection_vec();
-        let board_selection_vec = self.get_board_selections();
-        piece_source_vec.extend(board_selection_vec);
-        self.piece_source_vec = piece_source_vec;
+    fn redo(&mut self) {
+        let Some(redone_move) = self.redo_move_vec.pop() else {
+            return;
+        };
+        self.game_state
+            .apply(redone_move)
+            .expect("Undone moves were legal when they were played");
+        self.record.move_vec.push(redone_move);
+        self.finish_move();
+
+        if self.ai_has_turn() && !self.redo_move_vec.is_empty() {
+            self.redo();
+        }
     }
 
-    fn find_piece_destinations(&mut self) {
-        let mut piece_destination_vec = vec![];
-        let placeable_location_vec = self.find_placeable_locations();
-        piece_destination_vec.extend(placeable_location_vec);
-        let movable_location_vec = self.find_movable_locations();
-        piece_destination_vec.extend(movable_location_vec);
-        self.piece_destination_vec = piece_destination_vec;
+    fn ai_has_turn(&self) -> bool {
+        self.ai.is_some()
+            && self.state != State::Replay
+            && self.game_state.result().is_none()
+            && self.game_state.player_with_turn().number == PlayerNumber::Two
     }
 
-    fn find_movable_locations(&mut self) -> Vec<Selection> {
-        let mut moveable_location_vec: Vec<Selection> = vec![];
-        let selection = self.get_piece_source();
-        if selection.location != Location::Board {
-            return moveable_location_vec;
+    fn play_ai_turn(&mut self) {
+        let Some(ai) = self.ai else {
+            return;
+        };
+        if !self.ai_has_turn() {
+            return;
         }
-        let piece_to_move = self.board[selection.row][selection.col];
-        // FIXME at some point
-        match piece_to_move.bug {
-            Bug::Grasshopper => {
-                moveable_location_vec = find_grasshopper_movable_location(self.board, selection);
-            }
-            Bug::Spider => {
-                moveable_location_vec = find_slide_locations(self.board, selection, 3);
-            }
-            Bug::Ant => {
-                moveable_location_vec = find_ant_locations(self.board, selection);
-            }
-            Bug::Queen => {
-                moveable_location_vec = find_slide_locations(self.board, selection, 1);
-            }
-            _ => {}
+        let ai_move = ai.best_move(&self.game_state);
+        self.play_move(ai_move);
+        self.update();
+        self.print();
+    }
+
+    fn finish_move(&mut self) {
+        self.save_record();
+        self.clear_selections();
+        if self.state == State::Replay {
+            return;
         }
-        moveable_location_vec
+        self.state = match self.game_state.result() {
+            Some(game_result) => State::GameOver(game_result),
+            None => State::SelectPiece,
+        };
     }
 
-    fn find_placeable_locations(&mut self) -> Vec<Selection> {
-        let mut placeable_location_vec: Vec<Selection> = vec![];
-        let selection = self.get_piece_source();
-        if selection.location != Location::Hand {
-            return placeable_location_vec;
+    // Saving is best effort so a full disk doesn't end the game
+    fn save_record(&self) {
+        let Some(record_path) = &self.record_path else {
+            return;
+        };
+        if let Err(error) = std::fs::write(record_path, self.record.to_string()) {
+            eprintln!("Could not save {}: {}", record_path, error);
         }
+    }
 
-        let mut locations_occupied = 0;
+    // Notation of the last move, written from the position it was played in
+    fn format_last_move(&self) -> String {
+        let mut previous_record = self.record.clone();
+        match previous_record.move_vec.pop() {
+            Some(last_move) => previous_record.replay().format_move(last_move),
+            None => "start".to_string(),
+        }
+    }
 
-        for (i, row) in self.board.iter().enumerate() {
-            for (j, _piece) in row.iter().enumerate() {
-                let current_location_occupied = self.board[i][j].player != PlayerNumber::None;
-                if current_location_occupied {
-                    locations_occupied += 1;
-                    continue;
-                }
+    fn find_piece_sources(&mut self) {
+        let mut piece_source_vec = vec![];
+
+        // Skip anything that has nowhere to go
+        for (i, piece) in self.game_state.player_with_turn().hand.iter().enumerate() {
+            let placeable = self.legal_move_vec.iter().any(
+                |legal_move| matches!(legal_move, Move::Place { bug, .. } if *bug == piece.bug),
+            );
+            if placeable {
+                piece_source_vec.push(Selection {
+                    location: Location::Hand,
+                    row: 0,
+                    col: i as i32,
+                });
+            }
+        }
 
-                let neighboring_piece_vec = get_neighboring_piece_vec(self.board, i, j);
-
-                let mut neighboring_piece_from_another_player = false;
-                let mut neighboring_piece_from_same_player = false;
-                for neighbor in neighboring_piece_vec {
-                    if neighbor.player == self.player_with_turn.number {
-                        neighboring_piece_from_same_player = true;
-                    } else if neighbor.player == PlayerNumber::None {
-                        // Do nothing
-                    } else {
-                        neighboring_piece_from_another_player = true;
-                    }
-                }
+        for legal_move in &self.legal_move_vec {
+            let source = match legal_move {
+                Move::Move { source, .. } => *source,
+                Move::Throw { pillbug, .. } => *pillbug,
+                _ => continue,
+            };
+            let selection = Selection::from_hex(source);
+            if !piece_source_vec.contains(&selection) {
+                piece_source_vec.push(selection);
+            }
+        }
 
-                if neighboring_piece_from_another_player {
-                    continue;
-                }
-                if !neighboring_piece_from_same_player {
-                    continue;
-                }
+        if self.piece_source_vec_index >= piece_source_vec.len() {
+            self.piece_source_vec_index = 0;
+        }
+        self.piece_source_vec = piece_source_vec;
+    }
 
-                placeable_location_vec.push(Selection {
-                    location: Location::Board,
-                    row: i,
-                    col: j,
-                });
+    fn find_thrown_pieces(&mut self) {
+        let piece_source = self.get_piece_source();
+        let mut thrown_piece_vec = vec![];
+        for legal_move in &self.legal_move_vec {
+            let Move::Throw {
+                pillbug, source, ..
+            } = legal_move
+            else {
+                continue;
+            };
+            let selection = Selection::from_hex(*source);
+            if Selection::from_hex(*pillbug) == piece_source
+                && !thrown_piece_vec.contains(&selection)
+            {
+                thrown_piece_vec.push(selection);
             }
         }
+        self.thrown_piece_vec = thrown_piece_vec;
+    }
 
-        // Handles the first turn for each player where they have no existing
-        // pieces to play off of
-        if locations_occupied <= 1 {
-            if self.player_with_turn.number == PlayerNumber::One {
-                placeable_location_vec.push(Selection {
-                    location: Location::Board,
-                    row: FIRST_LOCATION.0,
-                    col: FIRST_LOCATION.1,
-                });
-            } else if self.player_with_turn.number == PlayerNumber::Two {
-                placeable_location_vec.push(Selection {
-                    location: Location::Board,
-                    row: FIRST_LOCATION.0 - 2,
-                    col: FIRST_LOCATION.1,
-                }); // North
-                placeable_location_vec.push(Selection {
-                    location: Location::Board,
-                    row: FIRST_LOCATION.0 - 1,
-                    col: FIRST_LOCATION.1 - 1,
-                }); // Northwest
-                placeable_location_vec.push(Selection {
-                    location: Location::Board,
-                    row: FIRST_LOCATION.0 - 1,
-                    col: FIRST_LOCATION.1 + 1,
-                }); // Northeast
-                placeable_location_vec.push(Selection {
-                    location: Location::Board,
-                    row: FIRST_LOCATION.0 + 2,
-                    col: FIRST_LOCATION.1,
-                }); // South
-                placeable_location_vec.push(Selection {
-                    location: Location::Board,
-                    row: FIRST_LOCATION.0 + 1,
-                    col: FIRST_LOCATION.1 - 1,
-                }); // Southwest
-                placeable_location_vec.push(Selection {
-                    location: Location::Board,
-                    row: FIRST_LOCATION.0 + 1,
-                    col: FIRST_LOCATION.1 + 1,
-                }); // Southeast
-            } else {
-                panic!();
-            }
+    fn find_piece_destinations(&mut self) {
+        let piece_source = self.get_piece_source();
+        let thrown_piece = self.get_thrown_piece();
+        let throwing = self.state == State::SelectThrowingLocation
+            || self.state == State::ConfirmThrowingLocation;
+
+        let mut piece_destination_vec = vec![];
+        for legal_move in &self.legal_move_vec {
+            let destination = match *legal_move {
+                Move::Place { bug, destination }
+                    if !throwing
+                        && piece_source.location == Location::Hand
+                        && self.game_state.player_with_turn().hand[piece_source.col as usize]
+                            .bug
+                            == bug =>
+                {
+                    destination
+                }
+                Move::Move {
+                    source,
+                    destination,
+                } if !throwing && Selection::from_hex(source) == piece_source => destination,
+                Move::Throw {
+                    pillbug,
+                    source,
+                    destination,
+                } if throwing
+                    && Selection::from_hex(pillbug) == piece_source
+                    && Selection::from_hex(source) == thrown_piece =>
+                {
+                    destination
+                }
+                _ => continue,
+            };
+            piece_destination_vec.push(Selection::from_hex(destination));
         }
-        placeable_location_vec
+        self.piece_destination_vec = piece_destination_vec;
     }
 
     fn print_board(&self) {
-        for (i, row) in self.board.iter().enumerate() {
-            for (j, piece) in row.iter().enumerate() {
-                let piece_destination = self.get_piece_destination();
-                let piece_source = self.get_piece_source();
-                let destination_selected = i == piece_destination.row && j == piece_destination.col;
+        let board = self.game_state.board();
+        let piece_destination = self.get_piece_destination();
+        let piece_source = self.get_piece_source();
+        let thrown_piece = self.get_thrown_piece();
+        for cell_vec in render::find_board_rows(board) {
+            for cell in cell_vec {
+                let Some(position) = cell else {
+                    print_piece(&Piece::new(Bug::None, PlayerNumber::None, 0), false);
+                    continue;
+                };
+                let stack = board.stack(position);
+                let (i, j) = render::to_doubled(position);
+                let destination_selected = i == piece_destination.row
+                    && j == piece_destination.col
+                    && piece_destination.location == Location::Board;
+                let thrown_selected = i == thrown_piece.row && j == thrown_piece.col;
                 let mut source_selected = i == piece_source.row && j == piece_source.col;
                 source_selected &= piece_source.location == Location::Board;
                 match self.state {
-                    State::SelectPiece => piece.print(source_selected),
-                    State::SelectPlacingLocation => piece.print(destination_selected),
-                    State::ConfirmPlacingLocation => piece.print(destination_selected),
+                    State::SelectPiece => print_stack(stack, source_selected),
+                    State::SelectPlacingLocation => print_stack(stack, destination_selected),
+                    State::ConfirmPlacingLocation => print_stack(stack, destination_selected),
+                    State::SelectThrownPiece => print_stack(stack, thrown_selected),
+                    State::SelectThrowingLocation => print_stack(stack, destination_selected),
+                    State::ConfirmThrowingLocation => print_stack(stack, destination_selected),
+                    State::MustPass => print_stack(stack, false),
+                    State::GameOver(_) => print_stack(stack, false),
+                    State::Replay => print_stack(stack, false),
                 }
             }
             println!();
@@ -860,11 +865,44 @@ impl Game {
         println!();
         println!();
         println!();
-        print_prompt(&self.state, self.player_with_turn.number);
-        println!();
+        print_prompt(&self.state, self.game_state.player_with_turn().number);
+        if self.ai_has_turn() {
+            println!("                                                  Thinking...");
+        } else if self.state == State::SelectPiece && !self.thrown_piece_vec.is_empty() {
+            println!(
+                "                                                  Press {} to use the pillbug",
+                ABILITY_KEY
+            );
+        } else if self.state == State::Replay {
+            let move_count = self.record.move_vec.len() + self.redo_move_vec.len();
+            println!(
+                "                                                  Move {} of {}: {}",
+                self.record.move_vec.len(),
+                move_count,
+                self.format_last_move()
+            );
+        } else if self.state == State::SelectPiece
+            && (!self.record.move_vec.is_empty() || !self.redo_move_vec.is_empty())
+        {
+            println!(
+                "                                                  Press {} to undo or {} to redo",
+                UNDO_KEY, REDO_KEY
+            );
+        } else {
+            println!();
+        }
         let piece_source = self.get_piece_source();
-        self.player_with_turn.print_hand(piece_source.col, true);
-        self.player_without_turn.print_hand(piece_source.col, false);
+        let show_selection = piece_source.location == Location::Hand && self.state != State::Replay;
+        print_hand(
+            self.game_state.player_with_turn(),
+            piece_source.col as usize,
+            show_selection,
+        );
+        print_hand(
+            self.game_state.player_without_turn(),
+            piece_source.col as usize,
+            false,
+        );
         println!();
         self.print_board();
     }
@@ -881,6 +919,13 @@ enum State {
     // SelectPieceOnBoard,
     // SelectMovingLocation,
     // ConfirmMovingLocation,
+    SelectThrownPiece,
+    SelectThrowingLocation,
+    ConfirmThrowingLocation,
+    MustPass,
+    GameOver(GameResult),
+    // Stepping through a recorded game
+    Replay,
 }
 
 fn print_prompt(state: &State, player_turn: PlayerNumber) {
@@ -890,11 +935,47 @@ fn print_prompt(state: &State, player_turn: PlayerNumber) {
         State::ConfirmPlacingLocation => {
             format!("Player {}: Are you quite sure about that?", player_turn)
         }
+        State::SelectThrownPiece => format!("Player {}: Select a bug to throw", player_turn),
+        State::SelectThrowingLocation => {
+            format!("Player {}: Choose where to throw it", player_turn)
+        }
+        State::ConfirmThrowingLocation => {
+            format!("Player {}: Are you quite sure about that?", player_turn)
+        }
+        State::MustPass => format!(
+            "Player {}: Nowhere to go, you must pass ({})",
+            player_turn, ADVANCE_KEY
+        ),
+        State::GameOver(GameResult::PlayerOneWins) => {
+            format!(
+                "Player {} wins! Rematch? ({}/{})",
+                PlayerNumber::One,
+                ADVANCE_KEY,
+                BACK_KEY
+            )
+        }
+        State::GameOver(GameResult::PlayerTwoWins) => {
+            format!(
+                "Player {} wins! Rematch? ({}/{})",
+                PlayerNumber::Two,
+                ADVANCE_KEY,
+                BACK_KEY
+            )
+        }
+        State::GameOver(GameResult::Draw) => {
+            format!("It's a draw! Rematch? ({}/{})", ADVANCE_KEY, BACK_KEY)
+        }
+        State::Replay => format!(
+            "Replay: step with {} and {}, quit with {}",
+            LEFT_KEY, RIGHT_KEY, BACK_KEY
+        ),
     };
-    let prompt_string_colored = if player_turn == PlayerNumber::One {
-        prompt_string.blue()
-    } else {
-        prompt_string.red()
+    let prompt_string_colored = match state {
+        State::GameOver(GameResult::PlayerOneWins) => prompt_string.blue(),
+        State::GameOver(GameResult::PlayerTwoWins) => prompt_string.red(),
+        State::GameOver(GameResult::Draw) => prompt_string.white(),
+        _ if player_turn == PlayerNumber::One => prompt_string.blue(),
+        _ => prompt_string.red(),
     };
     println!(
         "                                                  {}",
@@ -915,20 +996,3 @@ fn print_prompt(state: &State, player_turn: PlayerNumber) {
 //         value
 //     }
 // }
-
-fn create_hand(player: PlayerNumber) -> Vec<Piece> {
-    let hand: Vec<Piece> = vec![
-        Piece::new(Bug::Grasshopper, player),
-        Piece::new(Bug::Grasshopper, player),
-        Piece::new(Bug::Grasshopper, player),
-        Piece::new(Bug::Spider, player),
-        Piece::new(Bug::Spider, player),
-        Piece::new(Bug::Ant, player),
-        Piece::new(Bug::Ant, player),
-        Piece::new(Bug::Ant, player),
-        Piece::new(Bug::Queen, player),
-        Piece::new(Bug::Beetle, player),
-        Piece::new(Bug::Beetle, player),
-    ];
-    hand
-}
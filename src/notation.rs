@@ -0,0 +1,265 @@
+// Community move notation. A move names the piece that moves followed by a
+// reference piece it ends up next to, like "bS1 wQ/" or "wB2 bA3", and a
+// player with nothing to do plays "pass".
+//
+// The notation draws its hexes pointy side up, so every direction here is
+// turned a twelfth of a turn clockwise. North becomes northeast, northeast
+// becomes east and so on.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game_state::{GameState, Move};
+use crate::hex::{Direction, Hex, DIRECTION_ARR};
+use crate::piece::{Bug, Piece, PlayerNumber};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NotationError {
+    Malformed,
+    UnknownPiece,
+    MissingReference,
+    ReferenceNotOnBoard,
+    CoveredPiece,
+    IllegalMove,
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotationError::Malformed => write!(f, "Moves are a piece and a reference"),
+            NotationError::UnknownPiece => write!(f, "Unknown piece"),
+            NotationError::MissingReference => write!(f, "The move needs a reference piece"),
+            NotationError::ReferenceNotOnBoard => {
+                write!(f, "The reference piece is not on the board")
+            }
+            NotationError::CoveredPiece => write!(f, "The piece is covered"),
+            NotationError::IllegalMove => write!(f, "That move is not legal"),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+// Pieces are named by color, bug and ordinal like "wA1". Bugs with only one
+// piece per player leave the ordinal off, though "wQ1" is read as well.
+impl fmt::Display for Piece {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let color = match self.player {
+            PlayerNumber::Two => 'b',
+            _ => 'w',
+        };
+        match self.bug {
+            Bug::Queen | Bug::Mosquito | Bug::Ladybug | Bug::Pillbug => {
+                write!(f, "{}{}", color, self.bug)
+            }
+            _ => write!(f, "{}{}{}", color, self.bug, self.ordinal),
+        }
+    }
+}
+
+impl FromStr for Piece {
+    type Err = NotationError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let mut character_iter = string.chars();
+        let player = match character_iter.next() {
+            Some('w') => PlayerNumber::One,
+            Some('b') => PlayerNumber::Two,
+            _ => return Err(NotationError::UnknownPiece),
+        };
+        let bug = match character_iter.next() {
+            Some('G') => Bug::Grasshopper,
+            Some('S') => Bug::Spider,
+            Some('A') => Bug::Ant,
+            Some('Q') => Bug::Queen,
+            Some('B') => Bug::Beetle,
+            Some('M') => Bug::Mosquito,
+            Some('L') => Bug::Ladybug,
+            Some('P') => Bug::Pillbug,
+            _ => return Err(NotationError::UnknownPiece),
+        };
+        let ordinal = match character_iter.as_str() {
+            "" => 1,
+            ordinal_string => ordinal_string
+                .parse()
+                .map_err(|_| NotationError::UnknownPiece)?,
+        };
+        Ok(Piece::new(bug, player, ordinal))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+// The direction from a reference piece to the destination is written as a
+// mark before or after the reference
+fn format_reference(reference: Piece, direction: Direction) -> String {
+    match direction {
+        Direction::North => format!("{}/", reference),
+        Direction::Northeast => format!("{}-", reference),
+        Direction::Southeast => format!("{}\\", reference),
+        Direction::South => format!("/{}", reference),
+        Direction::Southwest => format!("-{}", reference),
+        Direction::Northwest => format!("\\{}", reference),
+    }
+}
+
+// A reference without a mark means on top of that piece
+fn parse_reference(string: &str) -> Result<(Piece, Option<Direction>), NotationError> {
+    let (piece_string, direction) = if let Some(piece_string) = string.strip_suffix('/') {
+        (piece_string, Some(Direction::North))
+    } else if let Some(piece_string) = string.strip_suffix('-') {
+        (piece_string, Some(Direction::Northeast))
+    } else if let Some(piece_string) = string.strip_suffix('\\') {
+        (piece_string, Some(Direction::Southeast))
+    } else if let Some(piece_string) = string.strip_prefix('/') {
+        (piece_string, Some(Direction::South))
+    } else if let Some(piece_string) = string.strip_prefix('-') {
+        (piece_string, Some(Direction::Southwest))
+    } else if let Some(piece_string) = string.strip_prefix('\\') {
+        (piece_string, Some(Direction::Northwest))
+    } else {
+        (string, None)
+    };
+    Ok((piece_string.parse()?, direction))
+}
+
+////////////////////////////////////////////////////////////////////////
+
+// A move only has a name in the position it is played from, since the
+// reference pieces depend on what is around the destination
+impl GameState {
+    // The piece a move picks up, which for a placement is the lowest one in hand
+    pub fn find_moving_piece(&self, selected_move: Move) -> Option<Piece> {
+        match selected_move {
+            Move::Place { bug, .. } => self
+                .player_with_turn()
+                .hand
+                .iter()
+                .find(|piece| piece.bug == bug)
+                .copied(),
+            Move::Move { source, .. } | Move::Throw { source, .. } => {
+                Some(self.board().top(source))
+            }
+            Move::Pass => None,
+        }
+    }
+
+    pub fn format_move(&self, selected_move: Move) -> String {
+        let board = self.board();
+        let (source, destination) = match selected_move {
+            Move::Place { destination, .. } => (None, destination),
+            Move::Move {
+                source,
+                destination,
+            }
+            | Move::Throw {
+                source,
+                destination,
+                ..
+            } => (Some(source), destination),
+            Move::Pass => return "pass".to_string(),
+        };
+        let Some(piece) = self.find_moving_piece(selected_move) else {
+            return "pass".to_string();
+        };
+
+        // Climbing onto the hive is written as the piece that ends up underneath
+        if board.is_occupied(destination) {
+            return format!("{} {}", piece, board.top(destination));
+        }
+
+        for direction in DIRECTION_ARR {
+            let reference_position = destination.neighbor(direction);
+            let stack_piece_vec = board.stack(reference_position).pieces();
+
+            // The moving piece can't be its own reference
+            let reference = if Some(reference_position) == source {
+                stack_piece_vec.iter().rev().nth(1)
+            } else {
+                stack_piece_vec.last()
+            };
+            if let Some(reference) = reference {
+                return format!(
+                    "{} {}",
+                    piece,
+                    format_reference(*reference, direction.rotate(3))
+                );
+            }
+        }
+
+        // Nothing to refer to before the first piece is down
+        piece.to_string()
+    }
+
+    // Reads a move in notation and finds the legal move it stands for
+    pub fn parse_move(&self, move_string: &str) -> Result<Move, NotationError> {
+        let board = self.board();
+        let token_vec: Vec<&str> = move_string.split_whitespace().collect();
+        let (piece_string, reference_string) = match token_vec.as_slice() {
+            ["pass"] => return self.find_legal_move(Move::Pass),
+            [piece_string] => (*piece_string, None),
+            [piece_string, reference_string] => (*piece_string, Some(*reference_string)),
+            _ => return Err(NotationError::Malformed),
+        };
+
+        let piece: Piece = piece_string.parse()?;
+        let destination = match reference_string {
+            None if board.occupied_positions().is_empty() => Hex::ORIGIN,
+            None => return Err(NotationError::MissingReference),
+            Some(reference_string) => {
+                let (reference, direction) = parse_reference(reference_string)?;
+                let reference_position = board
+                    .find_piece(reference)
+                    .map_err(|_| NotationError::ReferenceNotOnBoard)?;
+                match direction {
+                    Some(direction) => reference_position.neighbor(direction),
+                    None => reference_position,
+                }
+            }
+        };
+
+        let Ok(source) = board.find_piece(piece) else {
+            let placement = Move::Place {
+                bug: piece.bug,
+                destination,
+            };
+            if self.find_moving_piece(placement) != Some(piece) {
+                return Err(NotationError::IllegalMove);
+            }
+            return self.find_legal_move(placement);
+        };
+        if board.top(source) != piece {
+            return Err(NotationError::CoveredPiece);
+        }
+        self.find_move_between(source, destination)
+    }
+
+    // The legal move that takes the piece on top at the source to the
+    // destination. A regular move wins over a pillbug throwing the piece to
+    // the same place.
+    pub fn find_move_between(&self, source: Hex, destination: Hex) -> Result<Move, NotationError> {
+        let legal_move_vec = self.legal_moves();
+        let regular_move = Move::Move {
+            source,
+            destination,
+        };
+        if legal_move_vec.contains(&regular_move) {
+            return Ok(regular_move);
+        }
+        legal_move_vec
+            .into_iter()
+            .find(|legal_move| {
+                matches!(legal_move, Move::Throw { source: thrown, destination: thrown_to, .. }
+                    if *thrown == source && *thrown_to == destination)
+            })
+            .ok_or(NotationError::IllegalMove)
+    }
+
+    fn find_legal_move(&self, selected_move: Move) -> Result<Move, NotationError> {
+        if self.legal_moves().contains(&selected_move) {
+            Ok(selected_move)
+        } else {
+            Err(NotationError::IllegalMove)
+        }
+    }
+}
@@ -0,0 +1,64 @@
+// Perft walks the whole move tree down to some depth and counts the positions
+// at the bottom. The counts only depend on the rules, so they catch move
+// generation changing by accident.
+
+use crate::game_state::GameState;
+
+impl GameState {
+    // Every sequence of legal moves that is depth long counts once, so the
+    // same position reached in different orders counts more than once
+    pub fn perft(&self, depth: usize) -> u64 {
+        let mut game_state = self.clone();
+        count_leaf_positions(&mut game_state, depth)
+    }
+}
+
+fn count_leaf_positions(game_state: &mut GameState, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let move_vec = game_state.legal_moves();
+    // The moves themselves are the leaves one ply up
+    if depth == 1 {
+        return move_vec.len() as u64;
+    }
+
+    let mut leaf_count = 0;
+    for legal_move in move_vec {
+        game_state.play(legal_move);
+        leaf_count += count_leaf_positions(game_state, depth - 1);
+        game_state.undo();
+    }
+    leaf_count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::Expansions;
+
+    const ALL_EXPANSIONS: Expansions = Expansions {
+        mosquito: true,
+        ladybug: true,
+        pillbug: true,
+    };
+
+    #[test]
+    fn base_game_opening() {
+        let game_state = GameState::new(Expansions::default());
+        // Five bugs at the origin, then five bugs at six places around it
+        assert_eq!(game_state.perft(0), 1);
+        assert_eq!(game_state.perft(1), 5);
+        assert_eq!(game_state.perft(2), 150);
+        // A second placement has three places away from the other color, and
+        // a queen placed first can also slide to either side
+        assert_eq!(game_state.perft(3), 4 * 30 * 5 * 3 + 30 * (4 * 3 + 2));
+    }
+
+    #[test]
+    fn expansions_opening() {
+        let game_state = GameState::new(ALL_EXPANSIONS);
+        assert_eq!(game_state.perft(1), 8);
+        assert_eq!(game_state.perft(2), 8 * 8 * 6);
+    }
+}
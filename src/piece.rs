@@ -0,0 +1,108 @@
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Bug {
+    None,
+    Grasshopper,
+    Spider,
+    Ant,
+    Queen,
+    Beetle,
+    Mosquito,
+    Pillbug,
+    Ladybug,
+}
+
+impl fmt::Display for Bug {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Bug::None => write!(f, " "),
+            Bug::Grasshopper => write!(f, "G"),
+            Bug::Spider => write!(f, "S"),
+            Bug::Ant => write!(f, "A"),
+            Bug::Queen => write!(f, "Q"),
+            Bug::Beetle => write!(f, "B"),
+            Bug::Mosquito => write!(f, "M"),
+            Bug::Ladybug => write!(f, "L"),
+            Bug::Pillbug => write!(f, "P"),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PlayerNumber {
+    None,
+    One,
+    Two,
+}
+
+impl fmt::Display for PlayerNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayerNumber::One => write!(f, "1"),
+            PlayerNumber::Two => write!(f, "2"),
+            PlayerNumber::None => write!(f, "!"),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+// The ordinal tells apart pieces of the same bug, so the second ant a
+// player places is always ordinal 2. Empty cells are ordinal 0.
+pub struct Piece {
+    pub bug: Bug,
+    pub player: PlayerNumber,
+    pub ordinal: u8,
+}
+
+impl Piece {
+    pub const fn new(bug: Bug, player: PlayerNumber, ordinal: u8) -> Self {
+        Piece {
+            bug,
+            player,
+            ordinal,
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////
+
+// Optional expansion pieces added to each hand
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Expansions {
+    pub mosquito: bool,
+    pub ladybug: bool,
+    pub pillbug: bool,
+}
+
+// Pieces of the same bug are kept in ordinal order so the lowest one is
+// always placed first
+pub fn create_hand(player: PlayerNumber, expansions: Expansions) -> Vec<Piece> {
+    let mut hand: Vec<Piece> = vec![
+        Piece::new(Bug::Grasshopper, player, 1),
+        Piece::new(Bug::Grasshopper, player, 2),
+        Piece::new(Bug::Grasshopper, player, 3),
+        Piece::new(Bug::Spider, player, 1),
+        Piece::new(Bug::Spider, player, 2),
+        Piece::new(Bug::Ant, player, 1),
+        Piece::new(Bug::Ant, player, 2),
+        Piece::new(Bug::Ant, player, 3),
+        Piece::new(Bug::Queen, player, 1),
+        Piece::new(Bug::Beetle, player, 1),
+        Piece::new(Bug::Beetle, player, 2),
+    ];
+    if expansions.mosquito {
+        hand.push(Piece::new(Bug::Mosquito, player, 1));
+    }
+    if expansions.ladybug {
+        hand.push(Piece::new(Bug::Ladybug, player, 1));
+    }
+    if expansions.pillbug {
+        hand.push(Piece::new(Bug::Pillbug, player, 1));
+    }
+    hand
+}
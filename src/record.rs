@@ -0,0 +1,212 @@
+// Game records in the pgn-like format Boardspace and Entomology use. Tag pairs
+// come first and every move after them on its own numbered line.
+//
+// [GameType "Base+MLP"]
+// [Result "*"]
+// [RepetitionDraw "Off"]
+//
+// 1. wS1
+// 2. bG1 -wS1
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game_state::{GameResult, GameState, Move};
+use crate::notation::NotationError;
+use crate::piece::Expansions;
+
+const BASE_GAME_TYPE: &str = "Base";
+// Only games played without repetition draws carry this tag
+const REPETITION_DRAW_TAG: &str = "RepetitionDraw";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RecordError {
+    UnknownGameType,
+    MalformedTag,
+    // The number of the move that could not be played
+    IllegalMove(usize, NotationError),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::UnknownGameType => write!(f, "Unknown game type"),
+            RecordError::MalformedTag => write!(f, "Tags look like [Name \"Value\"]"),
+            RecordError::IllegalMove(number, notation_error) => {
+                write!(f, "Move {}: {}", number, notation_error)
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+// Game types are the base game plus the letters of any expansions, like
+// "Base+ML"
+impl fmt::Display for Expansions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", BASE_GAME_TYPE)?;
+        if !self.mosquito && !self.ladybug && !self.pillbug {
+            return Ok(());
+        }
+        write!(f, "+")?;
+        if self.mosquito {
+            write!(f, "M")?;
+        }
+        if self.ladybug {
+            write!(f, "L")?;
+        }
+        if self.pillbug {
+            write!(f, "P")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Expansions {
+    type Err = RecordError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let mut expansions = Expansions::default();
+        let expansion_string = match string.split_once('+') {
+            Some((BASE_GAME_TYPE, expansion_string)) => expansion_string,
+            None if string == BASE_GAME_TYPE => "",
+            _ => return Err(RecordError::UnknownGameType),
+        };
+        for character in expansion_string.chars() {
+            match character {
+                'M' => expansions.mosquito = true,
+                'L' => expansions.ladybug = true,
+                'P' => expansions.pillbug = true,
+                _ => return Err(RecordError::UnknownGameType),
+            }
+        }
+        Ok(expansions)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    pub expansions: Expansions,
+    // Whether the game was played with threefold repetition draws
+    pub repetition_draw: bool,
+    pub move_vec: Vec<Move>,
+}
+
+impl GameRecord {
+    pub fn new(expansions: Expansions) -> Self {
+        GameRecord {
+            expansions,
+            repetition_draw: true,
+            move_vec: vec![],
+        }
+    }
+
+    // The position before the first move, under the rules the game was
+    // played with
+    pub fn new_game_state(&self) -> GameState {
+        let mut game_state = GameState::new(self.expansions);
+        game_state.set_repetition_draw(self.repetition_draw);
+        game_state
+    }
+
+    // Plays every move from the start. Only legal moves end up in a record.
+    pub fn replay(&self) -> GameState {
+        let mut game_state = self.new_game_state();
+        for recorded_move in &self.move_vec {
+            game_state
+                .apply(*recorded_move)
+                .expect("Records only hold legal moves");
+        }
+        game_state
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut game_state = self.new_game_state();
+        let mut move_string_vec = vec![];
+        for recorded_move in &self.move_vec {
+            move_string_vec.push(game_state.format_move(*recorded_move));
+            game_state
+                .apply(*recorded_move)
+                .expect("Records only hold legal moves");
+        }
+
+        let result_string = match game_state.result() {
+            Some(GameResult::PlayerOneWins) => "1-0",
+            Some(GameResult::PlayerTwoWins) => "0-1",
+            Some(GameResult::Draw) => "1/2-1/2",
+            None => "*",
+        };
+        writeln!(f, "[GameType \"{}\"]", self.expansions)?;
+        writeln!(f, "[Result \"{}\"]", result_string)?;
+        if !self.repetition_draw {
+            writeln!(f, "[{} \"Off\"]", REPETITION_DRAW_TAG)?;
+        }
+        writeln!(f)?;
+        for (i, move_string) in move_string_vec.iter().enumerate() {
+            writeln!(f, "{}. {}", i + 1, move_string)?;
+        }
+        Ok(())
+    }
+}
+
+// Tags other than the game type and repetition draws are skipped, and so are
+// the move numbers. The result is worked out again from the moves.
+impl FromStr for GameRecord {
+    type Err = RecordError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let mut record = GameRecord::new(Expansions::default());
+        let mut game_state = record.new_game_state();
+        for line in string.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(tag) = line.strip_prefix('[') {
+                // A tag can change the rules, so the moves before it would
+                // have been played under different ones
+                if !record.move_vec.is_empty() {
+                    return Err(RecordError::MalformedTag);
+                }
+                let (name, value) = tag
+                    .strip_suffix(']')
+                    .and_then(|tag| tag.split_once(' '))
+                    .ok_or(RecordError::MalformedTag)?;
+                let value = value.trim_matches('"');
+                match name {
+                    "GameType" => record.expansions = value.parse()?,
+                    REPETITION_DRAW_TAG => {
+                        record.repetition_draw = match value {
+                            "On" => true,
+                            "Off" => false,
+                            _ => return Err(RecordError::MalformedTag),
+                        }
+                    }
+                    _ => continue,
+                }
+                game_state = record.new_game_state();
+                continue;
+            }
+
+            let move_string = match line.split_once(". ") {
+                Some((number, move_string)) if number.parse::<usize>().is_ok() => move_string,
+                _ => line,
+            };
+            let number = record.move_vec.len() + 1;
+            let recorded_move = game_state
+                .parse_move(move_string)
+                .map_err(|notation_error| RecordError::IllegalMove(number, notation_error))?;
+            game_state
+                .apply(recorded_move)
+                .map_err(|_| RecordError::IllegalMove(number, NotationError::IllegalMove))?;
+            record.move_vec.push(recorded_move);
+        }
+        Ok(record)
+    }
+}
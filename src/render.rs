@@ -0,0 +1,45 @@
+// Board geometry shared by everything that prints the board. Hexes are laid
+// out in doubled rows, where a hex sits at row 2r+q and column q, so only every
+// other cell of a row is a hex and the next column over is half a row off.
+
+use hive::{Board, Hex};
+
+// Empty rows and columns shown around the hive. Every destination is next to
+// a piece so this keeps them all in view.
+const DISPLAY_MARGIN: i32 = 2;
+
+pub fn to_doubled(hex: Hex) -> (i32, i32) {
+    (2 * hex.r + hex.q, hex.q)
+}
+
+// Only cells where the row and column are both even or both odd are hexes
+pub fn from_doubled(row: i32, col: i32) -> Hex {
+    Hex::new(col, (row - col) / 2)
+}
+
+// Every row of the grid around the hive, top to bottom, with its cells from
+// left to right. The cells between hexes are left empty.
+pub fn find_board_rows(board: &Board) -> Vec<Vec<Option<Hex>>> {
+    let mut position_vec = board.occupied_positions();
+    if position_vec.is_empty() {
+        position_vec.push(Hex::ORIGIN);
+    }
+
+    let (mut min_row, mut min_col) = to_doubled(position_vec[0]);
+    let (mut max_row, mut max_col) = (min_row, min_col);
+    for position in position_vec {
+        let (row, col) = to_doubled(position);
+        min_row = min_row.min(row - DISPLAY_MARGIN);
+        min_col = min_col.min(col - DISPLAY_MARGIN);
+        max_row = max_row.max(row + DISPLAY_MARGIN);
+        max_col = max_col.max(col + DISPLAY_MARGIN);
+    }
+
+    (min_row..=max_row)
+        .map(|row| {
+            (min_col..=max_col)
+                .map(|col| ((row - col) % 2 == 0).then(|| from_doubled(row, col)))
+                .collect()
+        })
+        .collect()
+}
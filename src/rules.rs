@@ -0,0 +1,425 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::board::{Board, FindError};
+use crate::hex::{Direction, Hex, DIRECTION_ARR};
+use crate::piece::{Bug, Piece, PlayerNumber};
+
+pub const SPIDER_STEPS: usize = 3;
+
+//////////////////////////////////////////////////////////////////////
+// Movement
+//////////////////////////////////////////////////////////////////////
+
+// Moves the piece at the position as if it were the given bug
+pub(crate) fn find_bug_locations(board: &Board, position: Hex, bug: Bug) -> Vec<Hex> {
+    match bug {
+        Bug::Grasshopper => find_grasshopper_locations(board, position),
+        Bug::Spider => find_spider_locations(board, position),
+        Bug::Ant => find_ant_locations(board, position),
+        Bug::Queen => find_queen_locations(board, position),
+        Bug::Beetle => find_beetle_locations(board, position),
+        Bug::Mosquito => find_mosquito_locations(board, position),
+        Bug::Ladybug => find_ladybug_locations(board, position),
+        Bug::Pillbug => find_queen_locations(board, position),
+        Bug::None => vec![],
+    }
+}
+
+fn find_grasshopper_locations(board: &Board, position: Hex) -> Vec<Hex> {
+    let mut location_vec = vec![];
+    for direction in DIRECTION_ARR {
+        if let Ok(moved_position) = test_grasshopper_direction(board, direction, position) {
+            location_vec.push(moved_position);
+        }
+    }
+    location_vec
+}
+
+fn test_grasshopper_direction(
+    board: &Board,
+    direction: Direction,
+    position: Hex,
+) -> Result<Hex, FindError> {
+    let starting_position = position.neighbor(direction);
+    let mut current_position = starting_position;
+    while board.is_occupied(current_position) {
+        current_position = current_position.neighbor(direction);
+    }
+    if current_position == starting_position {
+        return Err(FindError::NotFound);
+    }
+    Ok(current_position)
+}
+
+// A spider slides exactly three times around the hive without going back
+// over a cell it has already been on. Every path is walked since different
+// routes can end in different places.
+fn find_spider_locations(board: &Board, position: Hex) -> Vec<Hex> {
+    let mut board_clone = board.clone();
+    board_clone.pop(position);
+
+    let mut path = vec![position];
+    let mut location_vec = vec![];
+    walk_spider_paths(&board_clone, &mut path, &mut location_vec);
+    location_vec
+}
+
+fn walk_spider_paths(board: &Board, path: &mut Vec<Hex>, location_vec: &mut Vec<Hex>) {
+    let current_position = path[path.len() - 1];
+    if path.len() > SPIDER_STEPS {
+        if !location_vec.contains(&current_position) {
+            location_vec.push(current_position);
+        }
+        return;
+    }
+
+    for direction in DIRECTION_ARR {
+        let Ok(moved_position) = test_slide_direction(board, direction, current_position) else {
+            continue;
+        };
+        if path.contains(&moved_position) {
+            continue;
+        }
+        path.push(moved_position);
+        walk_spider_paths(board, path, location_vec);
+        path.pop();
+    }
+}
+
+// A mosquito moves like any bug it touches, or like a beetle while it is on
+// top of the hive. Touching only other mosquitoes leaves it with no moves.
+fn find_mosquito_locations(board: &Board, position: Hex) -> Vec<Hex> {
+    if board.height(position) > 1 {
+        return find_beetle_locations(board, position);
+    }
+
+    let mut location_vec = vec![];
+    let mut copied_bug_vec = vec![];
+    for neighbor in board.neighboring_stacks(position) {
+        let bug = neighbor.top().bug;
+        if bug == Bug::None || bug == Bug::Mosquito || copied_bug_vec.contains(&bug) {
+            continue;
+        }
+        copied_bug_vec.push(bug);
+        for location in find_bug_locations(board, position, bug) {
+            if !location_vec.contains(&location) {
+                location_vec.push(location);
+            }
+        }
+    }
+    location_vec
+}
+
+// Flood fill along the edge of the hive, one slide at a time
+fn find_ant_locations(board: &Board, position: Hex) -> Vec<Hex> {
+    let mut board_clone = board.clone();
+    board_clone.pop(position);
+
+    let mut traversed_set = HashSet::from([position]);
+    let mut current_vec = vec![position];
+    let mut location_vec = vec![];
+    while let Some(current_position) = current_vec.pop() {
+        for direction in DIRECTION_ARR {
+            let Ok(moved_position) =
+                test_slide_direction(&board_clone, direction, current_position)
+            else {
+                continue;
+            };
+            if traversed_set.insert(moved_position) {
+                current_vec.push(moved_position);
+                location_vec.push(moved_position);
+            }
+        }
+    }
+    location_vec
+}
+
+fn find_queen_locations(board: &Board, position: Hex) -> Vec<Hex> {
+    let mut board_clone = board.clone();
+    board_clone.pop(position);
+
+    let mut location_vec = vec![];
+    for direction in DIRECTION_ARR {
+        if let Ok(moved_position) = test_slide_direction(&board_clone, direction, position) {
+            location_vec.push(moved_position);
+        }
+    }
+    location_vec
+}
+
+// A piece on the ground can only slide into an empty neighbor when exactly
+// one of the two cells flanking the step is occupied. With both occupied the
+// gap is too narrow to fit through and with neither it would leave the hive.
+// The board must not contain the sliding piece.
+fn test_slide_direction(
+    board: &Board,
+    direction: Direction,
+    position: Hex,
+) -> Result<Hex, FindError> {
+    let moved_position = position.neighbor(direction);
+    if board.is_occupied(moved_position) {
+        return Err(FindError::NotFound);
+    }
+
+    let left_occupied = board.height_in_direction(position, direction.rotate(5)) > 0;
+    let right_occupied = board.height_in_direction(position, direction.rotate(1)) > 0;
+    if left_occupied == right_occupied {
+        return Err(FindError::NotFound);
+    }
+
+    Ok(moved_position)
+}
+
+// A beetle moves a single space but may climb on top of, across and down
+// from the hive
+fn find_beetle_locations(board: &Board, position: Hex) -> Vec<Hex> {
+    let mut board_clone = board.clone();
+    board_clone.pop(position);
+
+    let mut location_vec = vec![];
+    for direction in DIRECTION_ARR {
+        if let Ok(moved_position) = test_climb_direction(&board_clone, direction, position) {
+            location_vec.push(moved_position);
+        }
+    }
+    location_vec
+}
+
+// A ladybug takes two steps over the top of the hive and then one step down
+// into an empty space
+fn find_ladybug_locations(board: &Board, position: Hex) -> Vec<Hex> {
+    let mut board_clone = board.clone();
+    board_clone.pop(position);
+
+    let mut location_vec = vec![];
+    for first_direction in DIRECTION_ARR {
+        let Ok(first_position) = test_climb_direction(&board_clone, first_direction, position)
+        else {
+            continue;
+        };
+        if !board_clone.is_occupied(first_position) {
+            continue;
+        }
+
+        for second_direction in DIRECTION_ARR {
+            let Ok(second_position) =
+                test_climb_direction(&board_clone, second_direction, first_position)
+            else {
+                continue;
+            };
+            if second_position == position || !board_clone.is_occupied(second_position) {
+                continue;
+            }
+
+            for third_direction in DIRECTION_ARR {
+                let Ok(third_position) =
+                    test_climb_direction(&board_clone, third_direction, second_position)
+                else {
+                    continue;
+                };
+                if third_position == position || board_clone.is_occupied(third_position) {
+                    continue;
+                }
+                if !location_vec.contains(&third_position) {
+                    location_vec.push(third_position);
+                }
+            }
+        }
+    }
+    location_vec
+}
+
+// A piece on top of the stack at the position can step onto a neighboring
+// stack of any height unless both cells flanking the step are stacked higher
+// than both the cell it leaves and the cell it enters. The board must not
+// contain the moving piece.
+fn test_climb_direction(
+    board: &Board,
+    direction: Direction,
+    position: Hex,
+) -> Result<Hex, FindError> {
+    let moved_position = position.neighbor(direction);
+    let source_height = board.height(position);
+    let destination_height = board.height(moved_position);
+    let left_height = board.height_in_direction(position, direction.rotate(5));
+    let right_height = board.height_in_direction(position, direction.rotate(1));
+
+    // Sliding along the ground needs something to slide along
+    if source_height == 0 && destination_height == 0 && left_height == 0 && right_height == 0 {
+        return Err(FindError::NotFound);
+    }
+
+    if left_height.min(right_height) > source_height.max(destination_height) {
+        return Err(FindError::NotFound);
+    }
+
+    Ok(moved_position)
+}
+
+//////////////////////////////////////////////////////////////////////
+// Pillbug
+//////////////////////////////////////////////////////////////////////
+
+// A pillbug, or a mosquito touching one, can lift a neighboring piece over
+// itself as long as neither of them is part of a stack
+fn check_for_pillbug_ability(board: &Board, position: Hex) -> bool {
+    if board.height(position) != 1 {
+        return false;
+    }
+    match board.top(position).bug {
+        Bug::Pillbug => true,
+        Bug::Mosquito => board
+            .neighboring_stacks(position)
+            .iter()
+            .any(|neighbor| neighbor.top().bug == Bug::Pillbug),
+        _ => false,
+    }
+}
+
+// The piece the opponent just moved can't be thrown, nor can a piece that
+// would break the hive
+pub(crate) fn find_throwable_pieces(
+    board: &Board,
+    position: Hex,
+    last_moved_position: Option<Hex>,
+    pinned_position_set: &HashSet<Hex>,
+) -> Vec<Hex> {
+    let mut throwable_piece_vec = vec![];
+    if !check_for_pillbug_ability(board, position) {
+        return throwable_piece_vec;
+    }
+
+    for neighbor in position.neighbors() {
+        if board.height(neighbor) != 1 {
+            continue;
+        }
+        if last_moved_position == Some(neighbor) {
+            continue;
+        }
+        if pinned_position_set.contains(&neighbor) {
+            continue;
+        }
+        if find_throwing_locations(board, position, neighbor).is_empty() {
+            continue;
+        }
+        throwable_piece_vec.push(neighbor);
+    }
+    throwable_piece_vec
+}
+
+// The thrown piece climbs onto the pillbug and back down into an empty cell
+// next to it, passing the same gates a beetle would
+pub(crate) fn find_throwing_locations(
+    board: &Board,
+    position: Hex,
+    thrown_position: Hex,
+) -> Vec<Hex> {
+    let mut board_clone = board.clone();
+    board_clone.pop(thrown_position);
+
+    let mut location_vec = vec![];
+    let Some(direction) = thrown_position.direction_to(position) else {
+        return location_vec;
+    };
+    if test_climb_direction(&board_clone, direction, thrown_position).is_err() {
+        return location_vec;
+    }
+
+    for direction in DIRECTION_ARR {
+        let Ok(moved_position) = test_climb_direction(&board_clone, direction, position) else {
+            continue;
+        };
+        if moved_position == thrown_position || board_clone.is_occupied(moved_position) {
+            continue;
+        }
+        location_vec.push(moved_position);
+    }
+    location_vec
+}
+
+//////////////////////////////////////////////////////////////////////
+// Hive
+//////////////////////////////////////////////////////////////////////
+
+// A piece is pinned when lifting it would split the hive in two, which makes
+// it an articulation point of the graph of occupied cells. Every one of them
+// is found in a single depth first search. A piece with something under it
+// leaves its cell occupied, so it is never pinned.
+pub(crate) fn find_pinned_positions(board: &Board) -> HashSet<Hex> {
+    let mut pinned_position_set = HashSet::new();
+    let Some(first_position) = board.occupied_positions().first().copied() else {
+        return pinned_position_set;
+    };
+
+    let mut discovery_map = HashMap::new();
+    let mut low_map = HashMap::new();
+    search_articulation_points(
+        board,
+        first_position,
+        None,
+        &mut discovery_map,
+        &mut low_map,
+        &mut pinned_position_set,
+    );
+    pinned_position_set.retain(|position| board.height(*position) == 1);
+    pinned_position_set
+}
+
+// Tarjan's search. The low value of a cell is the earliest discovered cell it
+// can reach without going back through its parent. A child that can't reach
+// above its parent pins the parent.
+fn search_articulation_points(
+    board: &Board,
+    position: Hex,
+    parent: Option<Hex>,
+    discovery_map: &mut HashMap<Hex, usize>,
+    low_map: &mut HashMap<Hex, usize>,
+    pinned_position_set: &mut HashSet<Hex>,
+) {
+    let discovery = discovery_map.len();
+    discovery_map.insert(position, discovery);
+    let mut low = discovery;
+    let mut children = 0;
+
+    for neighbor in position.neighbors() {
+        if !board.is_occupied(neighbor) || Some(neighbor) == parent {
+            continue;
+        }
+        if let Some(neighbor_discovery) = discovery_map.get(&neighbor) {
+            low = low.min(*neighbor_discovery);
+            continue;
+        }
+
+        children += 1;
+        search_articulation_points(
+            board,
+            neighbor,
+            Some(position),
+            discovery_map,
+            low_map,
+            pinned_position_set,
+        );
+        let neighbor_low = low_map[&neighbor];
+        low = low.min(neighbor_low);
+        if parent.is_some() && neighbor_low >= discovery {
+            pinned_position_set.insert(position);
+        }
+    }
+
+    // The first cell only pins the hive when it splits it into separate parts
+    if parent.is_none() && children > 1 {
+        pinned_position_set.insert(position);
+    }
+    low_map.insert(position, low);
+}
+
+pub(crate) fn check_for_surrounded_queen(board: &Board, player: PlayerNumber) -> bool {
+    let queen = Piece::new(Bug::Queen, player, 1);
+    let Ok(position) = board.find_piece(queen) else {
+        return false;
+    };
+    board
+        .neighboring_stacks(position)
+        .iter()
+        .all(|stack| !stack.is_empty())
+}
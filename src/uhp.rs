@@ -0,0 +1,285 @@
+// Universal Hive Protocol engine. Commands come in one per line on stdin and
+// every response on stdout ends with "ok".
+
+use std::io::BufRead;
+use std::time::Duration;
+
+use hive::{Ai, Budget, Expansions, GameResult, GameState, NotationError, PlayerNumber, Strategy};
+
+const ENGINE_NAME: &str = "hive-rs";
+// Seconds to search when bestmove doesn't say
+const DEFAULT_SEARCH_TIME: u64 = 5;
+const REPETITION_DRAW_OPTION: &str = "RepetitionDraw";
+
+pub fn run() {
+    let mut engine = Engine {
+        game: None,
+        repetition_draw: true,
+    };
+    print_response(Ok(engine.info()));
+
+    let stdin = std::io::stdin();
+    for line in stdin.lock().lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "exit" {
+            return;
+        }
+        print_response(engine.handle(line));
+    }
+}
+
+fn print_response(response: Result<String, UhpError>) {
+    match response {
+        Ok(output) if output.is_empty() => {}
+        Ok(output) => println!("{}", output),
+        Err(UhpError::InvalidMove(message)) => println!("invalidmove {}", message),
+        Err(UhpError::Error(message)) => println!("err {}", message),
+    }
+    println!("ok");
+}
+
+////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum UhpError {
+    InvalidMove(String),
+    Error(String),
+}
+
+fn error(message: &str) -> UhpError {
+    UhpError::Error(message.to_string())
+}
+
+impl From<NotationError> for UhpError {
+    fn from(notation_error: NotationError) -> Self {
+        UhpError::InvalidMove(notation_error.to_string())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+struct Engine {
+    game: Option<UhpGame>,
+    // Carried over to every new game
+    repetition_draw: bool,
+}
+
+impl Engine {
+    fn handle(&mut self, line: &str) -> Result<String, UhpError> {
+        let (command, arguments) = match line.split_once(' ') {
+            Some((command, arguments)) => (command, arguments.trim()),
+            None => (line, ""),
+        };
+        match command {
+            "info" => Ok(self.info()),
+            "newgame" => {
+                self.game = Some(UhpGame::from_string(arguments, self.repetition_draw)?);
+                Ok(self.game()?.game_string())
+            }
+            "play" => {
+                let game = self.game_mut()?;
+                game.play(arguments)?;
+                Ok(game.game_string())
+            }
+            "pass" => {
+                let game = self.game_mut()?;
+                game.play("pass")?;
+                Ok(game.game_string())
+            }
+            "validmoves" => self.game()?.valid_moves(),
+            "bestmove" => self.game()?.best_move(parse_budget(arguments)?),
+            "undo" => {
+                let count = match arguments {
+                    "" => 1,
+                    _ => arguments
+                        .parse()
+                        .map_err(|_| error("Undo needs a number of moves"))?,
+                };
+                let game = self.game_mut()?;
+                game.undo(count)?;
+                Ok(game.game_string())
+            }
+            "options" => self.options(arguments),
+            _ => Err(error("Invalid command")),
+        }
+    }
+
+    fn info(&self) -> String {
+        format!(
+            "id {} v{}\nMosquito;Ladybug;Pillbug",
+            ENGINE_NAME,
+            env!("CARGO_PKG_VERSION")
+        )
+    }
+
+    // Options are listed as name;type;value;default
+    fn options(&mut self, arguments: &str) -> Result<String, UhpError> {
+        let token_vec: Vec<&str> = arguments.split_whitespace().collect();
+        match token_vec.as_slice() {
+            [] | ["get", REPETITION_DRAW_OPTION] => {}
+            ["set", REPETITION_DRAW_OPTION, value] => {
+                self.repetition_draw = match *value {
+                    "True" => true,
+                    "False" => false,
+                    _ => return Err(error("Set the option to True or False")),
+                };
+                if let Some(game) = &mut self.game {
+                    game.game_state.set_repetition_draw(self.repetition_draw);
+                }
+            }
+            ["get" | "set", ..] => return Err(error("Unknown option")),
+            _ => return Err(error("Options are read with get and changed with set")),
+        }
+        let format_bool = |value| if value { "True" } else { "False" };
+        Ok(format!(
+            "{};bool;{};{}",
+            REPETITION_DRAW_OPTION,
+            format_bool(self.repetition_draw),
+            format_bool(true)
+        ))
+    }
+
+    fn game(&self) -> Result<&UhpGame, UhpError> {
+        self.game
+            .as_ref()
+            .ok_or_else(|| error("No game in progress"))
+    }
+
+    fn game_mut(&mut self) -> Result<&mut UhpGame, UhpError> {
+        self.game
+            .as_mut()
+            .ok_or_else(|| error("No game in progress"))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+struct UhpGame {
+    expansions: Expansions,
+    game_state: GameState,
+    move_string_vec: Vec<String>,
+}
+
+impl UhpGame {
+    fn new(expansions: Expansions, repetition_draw: bool) -> Self {
+        let mut game_state = GameState::new(expansions);
+        game_state.set_repetition_draw(repetition_draw);
+        UhpGame {
+            expansions,
+            game_state,
+            move_string_vec: vec![],
+        }
+    }
+
+    // Accepts nothing, a game type like "Base+MLP" or a whole game string
+    fn from_string(string: &str, repetition_draw: bool) -> Result<Self, UhpError> {
+        if string.is_empty() {
+            return Ok(UhpGame::new(Expansions::default(), repetition_draw));
+        }
+
+        let mut field_iter = string.split(';');
+        let game_type = field_iter.next().unwrap_or_default();
+        let expansions = game_type.parse().map_err(|_| error("Unknown game type"))?;
+        let mut game = UhpGame::new(expansions, repetition_draw);
+        if string.contains(';') {
+            // The game state and turn fields follow from the moves
+            field_iter.next();
+            field_iter.next();
+            for move_string in field_iter {
+                game.play(move_string)?;
+            }
+        }
+        Ok(game)
+    }
+
+    fn play(&mut self, move_string: &str) -> Result<(), UhpError> {
+        if self.game_state.result().is_some() {
+            return Err(error("The game is over"));
+        }
+        let selected_move = self.game_state.parse_move(move_string)?;
+        let move_string = self.game_state.format_move(selected_move);
+        self.game_state
+            .apply(selected_move)
+            .map_err(|_| NotationError::IllegalMove)?;
+        self.move_string_vec.push(move_string);
+        Ok(())
+    }
+
+    fn undo(&mut self, count: usize) -> Result<(), UhpError> {
+        if count > self.move_string_vec.len() {
+            return Err(error("Not that many moves to undo"));
+        }
+        for _ in 0..count {
+            self.game_state.undo();
+            self.move_string_vec.pop();
+        }
+        Ok(())
+    }
+
+    fn valid_moves(&self) -> Result<String, UhpError> {
+        let move_string_vec: Vec<String> = self
+            .game_state
+            .legal_moves()
+            .into_iter()
+            .map(|legal_move| self.game_state.format_move(legal_move))
+            .collect();
+        Ok(move_string_vec.join(";"))
+    }
+
+    fn best_move(&self, budget: Budget) -> Result<String, UhpError> {
+        if self.game_state.result().is_some() {
+            return Err(error("The game is over"));
+        }
+        let best_move = Ai::new(Strategy::AlphaBeta, budget).best_move(&self.game_state);
+        Ok(self.game_state.format_move(best_move))
+    }
+
+    fn game_string(&self) -> String {
+        let game_state_string = match self.game_state.result() {
+            Some(GameResult::PlayerOneWins) => "WhiteWins",
+            Some(GameResult::PlayerTwoWins) => "BlackWins",
+            Some(GameResult::Draw) => "Draw",
+            None if self.move_string_vec.is_empty() => "NotStarted",
+            None => "InProgress",
+        };
+        let color = match self.game_state.player_with_turn().number {
+            PlayerNumber::Two => "Black",
+            _ => "White",
+        };
+        let turn = self.move_string_vec.len() / 2 + 1;
+
+        let mut field_vec = vec![
+            self.expansions.to_string(),
+            game_state_string.to_string(),
+            format!("{}[{}]", color, turn),
+        ];
+        field_vec.extend(self.move_string_vec.iter().cloned());
+        field_vec.join(";")
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+// Either "depth 3" or "time 00:00:05"
+fn parse_budget(arguments: &str) -> Result<Budget, UhpError> {
+    let budget_error = || error("Search for a depth or a time like 00:00:05");
+    match arguments.split_once(' ') {
+        None if arguments.is_empty() => Ok(Budget::Time(Duration::from_secs(DEFAULT_SEARCH_TIME))),
+        Some(("depth", depth)) => Ok(Budget::Depth(depth.parse().map_err(|_| budget_error())?)),
+        Some(("time", time)) => {
+            let mut seconds = 0;
+            for field in time.split(':') {
+                let field: u64 = field.parse().map_err(|_| budget_error())?;
+                seconds = seconds * 60 + field;
+            }
+            Ok(Budget::Time(Duration::from_secs(seconds)))
+        }
+        _ => Err(budget_error()),
+    }
+}
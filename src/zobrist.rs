@@ -0,0 +1,85 @@
+// Zobrist hashing. Every piece at every hex and stack height has its own
+// random key and a position hashes to the keys of its pieces xored together,
+// so moving a piece only takes two xors. The board has no edges, so the keys
+// are mixed up from the coordinates on the fly instead of kept in a table.
+
+use crate::game_state::Move;
+use crate::hex::Hex;
+use crate::piece::{Piece, PlayerNumber};
+
+// Xored in while player two has the turn
+pub const SIDE_KEY: u64 = 0x2545_f491_4f6c_dd1d;
+
+// SplitMix64 scrambles each input bit over the whole output
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// Height counts from 1 for the piece on the ground
+pub fn piece_key(piece: Piece, position: Hex, height: usize) -> u64 {
+    let identity = (piece.player as u64) << 16 | (piece.bug as u64) << 8 | piece.ordinal as u64;
+    let coordinates = (position.q as u32 as u64) << 32 | position.r as u32 as u64;
+    mix(mix(mix(identity) ^ coordinates) ^ height as u64)
+}
+
+pub fn side_key(player: PlayerNumber) -> u64 {
+    match player {
+        PlayerNumber::Two => SIDE_KEY,
+        _ => 0,
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    // The real score is at least this much
+    Lower,
+    // The real score is at most this much
+    Upper,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub hash: u64,
+    pub depth: usize,
+    pub score: i32,
+    pub bound: Bound,
+    pub best_move: Option<Move>,
+}
+
+// Searched positions by hash. Each hash has one slot and a deeper search
+// replaces a shallower one.
+#[derive(Debug, Clone)]
+pub struct TranspositionTable {
+    entry_vec: Vec<Option<Entry>>,
+}
+
+impl TranspositionTable {
+    // The size is rounded up to a power of two so a mask picks the slot
+    pub fn new(size: usize) -> Self {
+        TranspositionTable {
+            entry_vec: vec![None; size.max(1).next_power_of_two()],
+        }
+    }
+
+    fn slot(&self, hash: u64) -> usize {
+        hash as usize & (self.entry_vec.len() - 1)
+    }
+
+    pub fn get(&self, hash: u64) -> Option<Entry> {
+        self.entry_vec[self.slot(hash)].filter(|entry| entry.hash == hash)
+    }
+
+    pub fn insert(&mut self, entry: Entry) {
+        let slot = self.slot(entry.hash);
+        match self.entry_vec[slot] {
+            Some(existing) if existing.hash == entry.hash && existing.depth > entry.depth => {}
+            _ => self.entry_vec[slot] = Some(entry),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+// Game records have to play back under the rules the game was played with
+
+use hive::{Expansions, GameRecord, GameResult, GameState, RecordError};
+
+const OPENING: [&str; 4] = ["wQ", "bQ wQ-", "wA1 /wQ", "bA1 bQ\\"];
+// Both ants step out and back, which comes back to the position after the
+// opening
+const SHUFFLE: [&str; 4] = ["wA1 /bQ", "bA1 bQ-", "wA1 /wQ", "bA1 bQ\\"];
+
+// Plays the opening and then the shuffle the given number of times
+fn play(record: &mut GameRecord, shuffle_count: usize) -> GameState {
+    let mut game_state = record.new_game_state();
+    let shuffle_iter = SHUFFLE.iter().cycle().take(SHUFFLE.len() * shuffle_count);
+    for move_string in OPENING.iter().chain(shuffle_iter) {
+        let played_move = game_state.parse_move(move_string).unwrap();
+        game_state.apply(played_move).unwrap();
+        record.move_vec.push(played_move);
+    }
+    game_state
+}
+
+#[test]
+fn third_repetition_draws() {
+    let mut record = GameRecord::new(Expansions::default());
+    let game_state = play(&mut record, 2);
+    assert_eq!(game_state.result(), Some(GameResult::Draw));
+
+    let reloaded: GameRecord = record.to_string().parse().unwrap();
+    assert_eq!(reloaded, record);
+    assert_eq!(reloaded.replay().result(), Some(GameResult::Draw));
+}
+
+#[test]
+fn record_without_repetition_draws_plays_past_them() {
+    let mut record = GameRecord::new(Expansions::default());
+    record.repetition_draw = false;
+    let game_state = play(&mut record, 3);
+    assert_eq!(game_state.result(), None);
+
+    let record_string = record.to_string();
+    assert!(record_string.contains("[RepetitionDraw \"Off\"]"));
+    let reloaded: GameRecord = record_string.parse().unwrap();
+    assert_eq!(reloaded, record);
+
+    let replayed = reloaded.replay();
+    assert_eq!(replayed.result(), None);
+    assert_eq!(replayed.move_count(), record.move_vec.len());
+    assert_eq!(replayed.hash(), game_state.hash());
+}
+
+#[test]
+fn tags_after_the_moves_are_rejected() {
+    let mut record = GameRecord::new(Expansions::default());
+    play(&mut record, 0);
+    let record_string = format!("{}[GameType \"Base+MLP\"]\n", record);
+    assert_eq!(
+        record_string.parse::<GameRecord>(),
+        Err(RecordError::MalformedTag)
+    );
+}
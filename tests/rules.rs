@@ -0,0 +1,360 @@
+// Rules regression tests. Positions are written in a compact fixture format:
+// the player to move, "w" or "b", followed by every piece on the board as
+// piece@q,r in axial coordinates, bottom of each stack first.
+//
+//     w wQ@0,0 bQ@0,1 wB1@0,1
+//
+// Any expansion piece in the fixture switches its expansion on. Coordinates
+// follow the board, so 0,-1 is north of the origin and 1,-1 northeast of it.
+
+use hive::{Bug, Expansions, GameResult, GameState, Hex, Move, Piece, PlayerNumber};
+
+fn position(fixture: &str) -> GameState {
+    let mut token_iter = fixture.split_whitespace();
+    let player_with_turn = match token_iter.next() {
+        Some("w") => PlayerNumber::One,
+        Some("b") => PlayerNumber::Two,
+        _ => panic!("Fixtures start with the player to move: {}", fixture),
+    };
+
+    let mut expansions = Expansions::default();
+    let mut placed_piece_vec = vec![];
+    for token in token_iter {
+        let (piece_string, hex_string) = token
+            .split_once('@')
+            .unwrap_or_else(|| panic!("Pieces are written piece@q,r: {}", token));
+        let piece: Piece = piece_string
+            .parse()
+            .unwrap_or_else(|_| panic!("Unknown piece {}", piece_string));
+        match piece.bug {
+            Bug::Mosquito => expansions.mosquito = true,
+            Bug::Ladybug => expansions.ladybug = true,
+            Bug::Pillbug => expansions.pillbug = true,
+            _ => {}
+        }
+        placed_piece_vec.push((hex(hex_string), piece));
+    }
+    GameState::from_position(expansions, &placed_piece_vec, player_with_turn)
+        .unwrap_or_else(|| panic!("piece not in hand: {}", fixture))
+}
+
+fn hex(string: &str) -> Hex {
+    let (q, r) = string
+        .split_once(',')
+        .unwrap_or_else(|| panic!("Hexes are written q,r: {}", string));
+    Hex::new(q.parse().unwrap(), r.parse().unwrap())
+}
+
+// Space separated hexes, sorted to compare against the engine
+fn hexes(string: &str) -> Vec<Hex> {
+    let mut hex_vec: Vec<Hex> = string.split_whitespace().map(hex).collect();
+    hex_vec.sort();
+    hex_vec
+}
+
+fn move_destinations(game_state: &GameState, source: &str) -> Vec<Hex> {
+    let source = hex(source);
+    let mut destination_vec: Vec<Hex> = game_state
+        .legal_moves()
+        .into_iter()
+        .filter_map(|legal_move| match legal_move {
+            Move::Move {
+                source: moved_from,
+                destination,
+            } if moved_from == source => Some(destination),
+            _ => None,
+        })
+        .collect();
+    destination_vec.sort();
+    destination_vec
+}
+
+fn throw_destinations(game_state: &GameState, source: &str) -> Vec<Hex> {
+    let source = hex(source);
+    let mut destination_vec: Vec<Hex> = game_state
+        .legal_moves()
+        .into_iter()
+        .filter_map(|legal_move| match legal_move {
+            Move::Throw {
+                source: thrown_from,
+                destination,
+                ..
+            } if thrown_from == source => Some(destination),
+            _ => None,
+        })
+        .collect();
+    destination_vec.sort();
+    destination_vec
+}
+
+fn placement_destinations(game_state: &GameState, bug: Bug) -> Vec<Hex> {
+    let mut destination_vec: Vec<Hex> = game_state
+        .legal_moves()
+        .into_iter()
+        .filter_map(|legal_move| match legal_move {
+            Move::Place {
+                bug: placed_bug,
+                destination,
+            } if placed_bug == bug => Some(destination),
+            _ => None,
+        })
+        .collect();
+    destination_vec.sort();
+    destination_vec
+}
+
+////////////////////////////////////////////////////////////////////////
+// Placement
+////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn first_placement_is_at_the_origin() {
+    let game_state = position("w");
+    for bug in [
+        Bug::Queen,
+        Bug::Ant,
+        Bug::Grasshopper,
+        Bug::Spider,
+        Bug::Beetle,
+    ] {
+        assert_eq!(placement_destinations(&game_state, bug), hexes("0,0"));
+    }
+}
+
+#[test]
+fn second_placement_may_touch_the_other_color() {
+    let game_state = position("b wA1@0,0");
+    assert_eq!(
+        placement_destinations(&game_state, Bug::Spider),
+        hexes("0,-1 1,-1 1,0 0,1 -1,1 -1,0")
+    );
+}
+
+#[test]
+fn later_placements_only_touch_their_own_color() {
+    let game_state = position("w wA1@0,0 bA1@0,-1");
+    assert_eq!(
+        placement_destinations(&game_state, Bug::Ant),
+        hexes("1,0 0,1 -1,1")
+    );
+    // Nothing moves before the queen is down
+    assert_eq!(move_destinations(&game_state, "0,0"), hexes(""));
+}
+
+#[test]
+fn queen_is_placed_by_the_fourth_turn() {
+    let game_state = position("w wA1@0,0 bA1@0,-1 wA2@0,1 bA2@0,-2 wA3@0,2 bA3@0,-3");
+    assert_eq!(
+        placement_destinations(&game_state, Bug::Queen),
+        hexes("1,0 1,1 1,2 0,3 -1,3 -1,2 -1,1")
+    );
+    assert_eq!(placement_destinations(&game_state, Bug::Spider), hexes(""));
+}
+
+////////////////////////////////////////////////////////////////////////
+// Movement around two pieces
+////////////////////////////////////////////////////////////////////////
+
+// Unless it says otherwise the mover sits north of a white piece at the origin
+// with the black queen south of that
+#[test]
+fn queen_slides_one_space() {
+    let game_state = position("w wA1@0,0 bQ@0,1 wQ@0,-1");
+    assert_eq!(move_destinations(&game_state, "0,-1"), hexes("1,-1 -1,0"));
+}
+
+#[test]
+fn ant_goes_anywhere_around_the_hive() {
+    let game_state = position("w wQ@0,0 bQ@0,1 wA1@0,-1");
+    assert_eq!(
+        move_destinations(&game_state, "0,-1"),
+        hexes("1,-1 1,0 1,1 0,2 -1,2 -1,1 -1,0")
+    );
+}
+
+#[test]
+fn spider_walks_exactly_three_spaces() {
+    let game_state = position("w wQ@0,0 bQ@0,1 wS1@0,-1");
+    assert_eq!(move_destinations(&game_state, "0,-1"), hexes("1,1 -1,2"));
+}
+
+#[test]
+fn beetle_steps_or_climbs() {
+    let game_state = position("w wQ@0,0 bQ@0,1 wB1@0,-1");
+    assert_eq!(
+        move_destinations(&game_state, "0,-1"),
+        hexes("1,-1 0,0 -1,0")
+    );
+}
+
+#[test]
+fn ladybug_crosses_the_top_and_climbs_down() {
+    let game_state = position("w wQ@0,0 bQ@0,1 wL@0,-1");
+    assert_eq!(
+        move_destinations(&game_state, "0,-1"),
+        hexes("1,0 1,1 0,2 -1,2 -1,1")
+    );
+}
+
+#[test]
+fn mosquito_copies_its_neighbors() {
+    let game_state = position("w wQ@0,0 bQ@0,1 wM@0,-1");
+    assert_eq!(move_destinations(&game_state, "0,-1"), hexes("1,-1 -1,0"));
+    // Touching an ant as well adds every space around the hive
+    let game_state = position("w wQ@0,0 bQ@0,1 wA1@1,-1 wM@0,-1");
+    assert_eq!(
+        move_destinations(&game_state, "0,-1"),
+        hexes("1,-2 2,-2 2,-1 1,0 1,1 0,2 -1,2 -1,1 -1,0")
+    );
+}
+
+#[test]
+fn pillbug_moves_like_a_queen_and_throws_neighbors() {
+    let game_state = position("w wQ@0,0 bQ@0,1 wP@0,-1");
+    assert_eq!(move_destinations(&game_state, "0,-1"), hexes("1,-1 -1,0"));
+    // The white queen holds the hive together, so it can't be thrown
+    assert_eq!(throw_destinations(&game_state, "0,0"), hexes(""));
+
+    let game_state = position("w wP@0,0 bQ@0,-1 wQ@0,1");
+    assert_eq!(
+        throw_destinations(&game_state, "0,-1"),
+        hexes("1,-1 1,0 -1,1 -1,0")
+    );
+}
+
+////////////////////////////////////////////////////////////////////////
+// Gates
+////////////////////////////////////////////////////////////////////////
+
+// Five pieces curl around an empty hole at the origin, open to the northwest
+// where the mover sits. Pieces at 0,-1 and -1,1 gate the way in.
+const OPEN_RING: &str = "bQ@0,-1 wQ@1,-1 bA1@1,0 wA1@0,1 bA2@-1,1";
+
+#[test]
+fn queen_cannot_slide_through_a_gate() {
+    let game_state = position(&format!("w {} wQ@-1,0", OPEN_RING.replace("wQ", "wS1")));
+    assert_eq!(move_destinations(&game_state, "-1,0"), hexes("-1,-1 -2,1"));
+}
+
+#[test]
+fn ant_cannot_enter_a_gated_hole() {
+    let game_state = position(&format!("w {} wA2@-1,0", OPEN_RING));
+    assert_eq!(
+        move_destinations(&game_state, "-1,0"),
+        hexes("-2,1 -2,2 -1,-1 -1,2 0,-2 0,2 1,-2 1,1 2,-2 2,-1 2,0")
+    );
+}
+
+#[test]
+fn spider_walks_around_a_gated_hole() {
+    let game_state = position(&format!("w {} wS1@-1,0", OPEN_RING));
+    assert_eq!(move_destinations(&game_state, "-1,0"), hexes("1,-2 -1,2"));
+}
+
+#[test]
+fn ground_beetle_cannot_squeeze_through_a_gate() {
+    let game_state = position(&format!("w {} wB1@-1,0", OPEN_RING));
+    assert_eq!(
+        move_destinations(&game_state, "-1,0"),
+        hexes("-1,-1 0,-1 -1,1 -2,1")
+    );
+}
+
+#[test]
+fn beetle_on_top_drops_into_a_closed_hole() {
+    let game_state = position(&format!("w {} wA2@-1,0 wB1@0,-1", OPEN_RING));
+    assert_eq!(
+        move_destinations(&game_state, "0,-1"),
+        hexes("0,-2 1,-2 1,-1 0,0 -1,0 -1,-1")
+    );
+}
+
+////////////////////////////////////////////////////////////////////////
+// Grasshopper
+////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn grasshopper_stops_at_the_first_gap() {
+    // The line north has a gap at 0,-3 before the piece at 0,-4
+    let game_state = position("w wG1@0,0 bQ@0,-1 wQ@0,-2 bA1@1,-3 wA1@1,-4 bA2@0,-4");
+    assert_eq!(move_destinations(&game_state, "0,0"), hexes("0,-3"));
+}
+
+#[test]
+fn grasshopper_needs_something_to_jump_over() {
+    let game_state = position("w wQ@0,0 bQ@0,1 wG1@0,-1");
+    assert_eq!(move_destinations(&game_state, "0,-1"), hexes("0,2"));
+}
+
+////////////////////////////////////////////////////////////////////////
+// Pinned pieces
+////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn piece_holding_the_hive_together_is_pinned() {
+    let game_state = position("w bQ@0,-1 wQ@0,0 wA1@0,1");
+    assert_eq!(move_destinations(&game_state, "0,0"), hexes(""));
+    assert_eq!(
+        move_destinations(&game_state, "0,1"),
+        hexes("0,-2 1,-2 1,-1 1,0 -1,1 -1,0 -1,-1")
+    );
+}
+
+#[test]
+fn pieces_in_a_ring_are_not_pinned() {
+    let game_state = position(&format!("w {} wA2@-1,0", OPEN_RING));
+    // The ant can leave the ring but not slip through the gate into the hole
+    assert_eq!(
+        move_destinations(&game_state, "0,1"),
+        hexes("-2,0 -2,1 -2,2 -1,-1 -1,2 0,-2 1,-2 1,1 2,-2 2,-1 2,0")
+    );
+}
+
+#[test]
+fn beetle_on_a_pinned_piece_is_free() {
+    let game_state = position("w bQ@0,-1 wQ@0,0 wB1@0,0 wA1@0,1");
+    assert_eq!(
+        move_destinations(&game_state, "0,0"),
+        hexes("0,-1 1,-1 1,0 0,1 -1,1 -1,0")
+    );
+}
+
+////////////////////////////////////////////////////////////////////////
+// Queen surround
+////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn surrounding_the_queen_wins() {
+    let mut game_state = position("w bQ@0,0 wA1@0,-1 bA1@1,-1 wA2@1,0 bA2@0,1 wG1@-1,1 wQ@-2,1");
+    assert_eq!(game_state.result(), None);
+    game_state
+        .apply(Move::Move {
+            source: hex("-2,1"),
+            destination: hex("-1,0"),
+        })
+        .unwrap();
+    assert_eq!(game_state.result(), Some(GameResult::PlayerOneWins));
+    assert!(game_state.legal_moves().is_empty());
+}
+
+#[test]
+fn surrounding_both_queens_draws() {
+    // The grasshopper jumps north over -1,1 into the last space next to both
+    let mut game_state = position(
+        "w bQ@0,0 bA1@0,-1 bA2@1,-1 bA3@1,0 bS1@0,1 bS2@-1,1 \
+         wQ@-1,-1 wA1@-1,-2 wA2@0,-2 wA3@-2,0 wS1@-2,-1 wG1@-1,2",
+    );
+    game_state
+        .apply(Move::Move {
+            source: hex("-1,2"),
+            destination: hex("-1,0"),
+        })
+        .unwrap();
+    assert_eq!(game_state.result(), Some(GameResult::Draw));
+}
+
+#[test]
+fn fixture_already_surrounded_is_over() {
+    let game_state = position("b bQ@0,0 wA1@0,-1 bA1@1,-1 wA2@1,0 bA2@0,1 wG1@-1,1 wQ@-1,0");
+    assert_eq!(game_state.result(), Some(GameResult::PlayerOneWins));
+}